@@ -0,0 +1,405 @@
+use std::time::Duration;
+
+use hex_maze::{endpoint::Endpoint, generator, hex::Position, solver, style::Style};
+
+pub const USAGE: &str = "\
+Generates a maze on a hexagonal grid, then solves it.
+
+Usage: hex-maze [OPTIONS]
+
+Options:
+  -g, --generator <NAME>  Generation algorithm: dfs, prim [default: dfs]
+      --solver <NAME>     Solving algorithm: astar, dijkstra, bfs, dfs, left-hand,
+                          right-hand, tremaux, dead-end-fill [default: astar]
+  -s, --seed <SEED>       Seed for generation [default: random]
+      --braid <FRACTION>  Fraction of dead ends to remove, adding loops to the
+                          maze, from 0 to 1 [default: 0]
+      --size <COLSxROWS>  Maze size in cells [default: fit the terminal]
+  -d, --delay <MS>        Milliseconds between animation steps [default: 16]
+      --start <CELL>      Cell to solve from, and to generate from if it is
+                          COL,ROW [default: top-left]
+      --goal <CELL>       Cell to solve to; give more than once to solve to
+                          whichever is nearest [default: bottom-right]
+      --via <COL,ROW>     Cell to pass through on the way, in the order given;
+                          can be given more than once
+      --best-order        Pass through the --via cells in whichever order makes
+                          the shortest route (for up to 8 cells)
+      --openings          Cut openings in the outer wall by the start and goal
+      --terrain           Scatter mud and water, which are slow to cross, and
+                          roads, which are quick, for A* and dijkstra to avoid
+                          or follow
+      --style <NAME>      How to draw the maze: ascii, unicode, braille
+                          [default: ascii]
+      --no-animate        Show the finished maze and solution immediately
+      --play              Walk the maze yourself instead of watching it solved
+      --stats             Print statistics about the maze instead of showing it
+                          (the size defaults to 20x10)
+  -h, --help              Print this help
+
+Cells are given as COL,ROW counting from 0, or as random, or as farthest from
+the other end. With both ends farthest, they are the ends of a longest route.
+
+Keys:
+  r       Regenerate with a new seed
+  g       Switch generation algorithm, keeping the seed
+  a       Switch solving algorithm, solving the maze again
+  v       Switch drawing style
+  p       Play the finished maze
+  e       Edit the finished maze, then solve it again
+  h       Show or hide the distance from the start to every cell
+  q, Esc  Quit
+
+Mouse:
+  Left/right click a solved maze to solve from/to that cell.
+  Click the heatmap to measure distances from that cell.
+  Click walls while editing to toggle them, except in the braille style.
+  Click a neighboring cell while playing to move there.
+
+Keys while playing:
+  7 8 9 / u i o / Home Up PgUp    Move north-west, north, north-east
+  1 2 3 / j k l / End Down PgDn   Move south-west, south, south-east
+  s                               Show or hide the shortest route
+";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub generator: generator::Algorithm,
+    pub solver: solver::Algorithm,
+    pub seed: Option<u64>,
+    pub braid: f64,
+    pub size: Option<(u16, u16)>,
+    pub delay: Duration,
+    pub start: Option<Endpoint>,
+    pub goals: Vec<Endpoint>,
+    pub via: Vec<Position>,
+    pub best_order: bool,
+    pub openings: bool,
+    pub terrain: bool,
+    pub style: Style,
+    pub animate: bool,
+    pub play: bool,
+    pub stats: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            generator: generator::Algorithm::default(),
+            solver: solver::Algorithm::default(),
+            seed: None,
+            braid: 0.0,
+            size: None,
+            delay: Duration::from_millis(16),
+            start: None,
+            goals: Vec::new(),
+            via: Vec::new(),
+            best_order: false,
+            openings: false,
+            terrain: false,
+            style: Style::default(),
+            animate: true,
+            play: false,
+            stats: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run(Options),
+    Help,
+}
+
+/// Parses the command-line arguments, not including the program name.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_owned(), Some(value.to_owned()))
+            }
+            _ => (arg, None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{flag} requires a value"))
+        };
+
+        let is_switch = matches!(
+            flag.as_str(),
+            "--help"
+                | "--best-order"
+                | "--openings"
+                | "--terrain"
+                | "--no-animate"
+                | "--play"
+                | "--stats"
+        );
+        if is_switch && inline_value.is_some() {
+            return Err(format!("{flag} doesn't take a value"));
+        }
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-g" | "--generator" => options.generator = value()?.parse()?,
+            "--solver" => options.solver = value()?.parse()?,
+            "-s" | "--seed" => options.seed = Some(parse_number(&flag, &value()?)?),
+            "--braid" => {
+                options.braid = parse_number(&flag, &value()?)?;
+                if !(0.0..=1.0).contains(&options.braid) {
+                    return Err(format!("{flag} must be between 0 and 1"));
+                }
+            }
+            "--size" => options.size = Some(parse_size(&value()?)?),
+            "-d" | "--delay" => {
+                let millis: u64 = parse_number(&flag, &value()?)?;
+                if millis > i32::MAX as u64 {
+                    return Err(format!("{flag} must be at most {}", i32::MAX));
+                }
+                options.delay = Duration::from_millis(millis);
+            }
+            "--start" => options.start = Some(parse_endpoint(&flag, &value()?)?),
+            "--goal" => options.goals.push(parse_endpoint(&flag, &value()?)?),
+            "--via" => options.via.push(parse_position(&flag, &value()?)?),
+            "--best-order" => options.best_order = true,
+            "--openings" => options.openings = true,
+            "--terrain" => options.terrain = true,
+            "--style" => options.style = value()?.parse()?,
+            "--no-animate" => options.animate = false,
+            "--play" => options.play = true,
+            "--stats" => options.stats = true,
+            _ => return Err(format!("unexpected argument {flag:?}")),
+        }
+    }
+
+    if options.best_order && options.via.len() > solver::MAX_BEST_ORDER {
+        return Err(format!(
+            "--best-order takes at most {} --via cells",
+            solver::MAX_BEST_ORDER
+        ));
+    }
+    options.check_cells(options.size)?;
+    Ok(Command::Run(options))
+}
+
+impl Options {
+    /// Checks that the cells given for the start, goals and waypoints are in
+    /// a maze of the given size, or at least not before its first cell if
+    /// the size isn't known yet.
+    pub fn check_cells(&self, size: Option<(u16, u16)>) -> Result<(), String> {
+        let cells = self
+            .start
+            .map(|start| ("--start", start))
+            .into_iter()
+            .chain(self.goals.iter().map(|&goal| ("--goal", goal)))
+            .chain(self.via.iter().map(|&via| ("--via", Endpoint::Cell(via))));
+        for (flag, endpoint) in cells {
+            let Endpoint::Cell(position) = endpoint else {
+                continue;
+            };
+            let in_bounds = match size {
+                Some((cols, rows)) => position.col < cols as i16 && position.row < rows as i16,
+                None => true,
+            };
+            if position.col < 0 || position.row < 0 || !in_bounds {
+                return Err(format!("{flag} cell is outside the maze"));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, s: &str) -> Result<T, String> {
+    s.parse()
+        .map_err(|_| format!("invalid value {s:?} for {flag}"))
+}
+
+fn parse_size(s: &str) -> Result<(u16, u16), String> {
+    let invalid = || format!("invalid size {s:?}, expected COLSxROWS");
+    let (cols, rows) = s.split_once('x').ok_or_else(invalid)?;
+    let cols: u16 = cols.parse().map_err(|_| invalid())?;
+    let rows: u16 = rows.parse().map_err(|_| invalid())?;
+    if cols == 0 || rows == 0 || cols > i16::MAX as u16 || rows > i16::MAX as u16 / 2 {
+        return Err(format!("size {s:?} is out of range"));
+    }
+    Ok((cols, rows))
+}
+
+fn parse_endpoint(flag: &str, s: &str) -> Result<Endpoint, String> {
+    match s {
+        "random" => Ok(Endpoint::Random),
+        "farthest" => Ok(Endpoint::Farthest),
+        _ => parse_position(flag, s)
+            .map(Endpoint::Cell)
+            .map_err(|err| format!("{err}, random or farthest")),
+    }
+}
+
+fn parse_position(flag: &str, s: &str) -> Result<Position, String> {
+    let invalid = || format!("invalid cell {s:?} for {flag}, expected COL,ROW");
+    let (col, row) = s.split_once(',').ok_or_else(invalid)?;
+    Ok(Position {
+        col: col.trim().parse().map_err(|_| invalid())?,
+        row: row.trim().parse().map_err(|_| invalid())?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use hex_maze::{endpoint::Endpoint, generator, hex::Position, solver, style::Style};
+
+    use crate::cli::{parse, Command, Options};
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        assert_eq!(parse_args(&[]), Ok(Command::Run(Options::default())));
+        assert_eq!(parse_args(&["--seed", "1", "-h"]), Ok(Command::Help));
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(
+            parse_args(&[
+                "-g",
+                "prim",
+                "--seed=42",
+                "--braid",
+                "0.25",
+                "--size",
+                "20x10",
+                "-d",
+                "100",
+                "--start",
+                "1,2",
+                "--goal=19,9",
+                "--style",
+                "braille",
+                "--solver",
+                "tremaux",
+                "--no-animate",
+                "--play",
+                "--terrain",
+            ]),
+            Ok(Command::Run(Options {
+                generator: generator::Algorithm::Prim,
+                seed: Some(42),
+                braid: 0.25,
+                size: Some((20, 10)),
+                delay: Duration::from_millis(100),
+                start: Some(Endpoint::Cell(Position { col: 1, row: 2 })),
+                goals: vec![Endpoint::Cell(Position { col: 19, row: 9 })],
+                solver: solver::Algorithm::Tremaux,
+                style: Style::Braille,
+                animate: false,
+                play: true,
+                terrain: true,
+                ..Options::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_endpoints() {
+        assert_eq!(
+            parse_args(&["--start", "random", "--goal=farthest", "--openings"]),
+            Ok(Command::Run(Options {
+                start: Some(Endpoint::Random),
+                goals: vec![Endpoint::Farthest],
+                openings: true,
+                ..Options::default()
+            }))
+        );
+        // Only cells are checked against the size.
+        assert!(parse_args(&["--size", "4x4", "--goal", "farthest"]).is_ok());
+    }
+
+    #[test]
+    fn test_check_cells() {
+        // Without a size, cells are checked once the maze's size is known.
+        let Ok(Command::Run(options)) = parse_args(&["--start", "5,0", "--via", "0,5"]) else {
+            panic!("expected options");
+        };
+        assert!(options.check_cells(Some((6, 6))).is_ok());
+        assert_eq!(
+            options.check_cells(Some((5, 6))),
+            Err("--start cell is outside the maze".to_owned())
+        );
+        assert_eq!(
+            options.check_cells(Some((6, 5))),
+            Err("--via cell is outside the maze".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_parse_waypoints() {
+        assert_eq!(
+            parse_args(&[
+                "--goal",
+                "5,0",
+                "--goal",
+                "0,5",
+                "--via",
+                "1,2",
+                "--via=3,4",
+                "--best-order",
+            ]),
+            Ok(Command::Run(Options {
+                goals: vec![
+                    Endpoint::Cell(Position { col: 5, row: 0 }),
+                    Endpoint::Cell(Position { col: 0, row: 5 }),
+                ],
+                via: vec![Position { col: 1, row: 2 }, Position { col: 3, row: 4 }],
+                best_order: true,
+                ..Options::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_args(&["--generator", "kruskal"]).is_err());
+        assert!(parse_args(&["--seed"]).is_err());
+        assert!(parse_args(&["--seed", "-1"]).is_err());
+        assert!(parse_args(&["--braid", "2"]).is_err());
+        assert!(parse_args(&["--braid", "NaN"]).is_err());
+        assert!(parse_args(&["--size", "0x4"]).is_err());
+        assert!(parse_args(&["--size", "10"]).is_err());
+        assert!(parse_args(&["--start", "a,b"]).is_err());
+        assert!(parse_args(&["--start", "nearest"]).is_err());
+        assert!(parse_args(&["--start", "-1,0"]).is_err());
+        assert!(parse_args(&["--size", "4x4", "--goal", "4,0"]).is_err());
+        assert!(parse_args(&["--delay", "2147483648"]).is_err());
+        assert!(parse_args(&["--size", "4x4", "--goal", "1,1", "--goal", "0,4"]).is_err());
+        assert!(parse_args(&["--via", "random"]).is_err());
+        assert!(parse_args(&["--size", "4x4", "--via", "5,5"]).is_err());
+        let via = |count| (0..count).flat_map(|_| ["--via", "1,1"]);
+        assert!(parse_args(&via(8).chain(["--best-order"]).collect::<Vec<_>>()).is_ok());
+        assert!(parse_args(&via(9).collect::<Vec<_>>()).is_ok());
+        assert!(parse_args(&via(9).chain(["--best-order"]).collect::<Vec<_>>()).is_err());
+        assert!(parse_args(&["--style", "fancy"]).is_err());
+        assert!(parse_args(&["--frobnicate"]).is_err());
+        for switch in [
+            "--help",
+            "--best-order",
+            "--openings",
+            "--terrain",
+            "--no-animate",
+            "--play",
+            "--stats",
+        ] {
+            assert_eq!(
+                parse_args(&[&format!("{switch}=false")]),
+                Err(format!("{switch} doesn't take a value"))
+            );
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::env;
+
+use crate::terminal::PALETTE;
+
+/// A color for the terminal or for rendering.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// The terminal's own foreground or background color.
+    #[default]
+    Default,
+    /// One of the 16 basic colors, whose exact shades depend on the terminal.
+    Basic(u8),
+    /// One of the 256 colors of the xterm palette. The first 16 are the basic
+    /// colors.
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// How many colors a terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorSupport {
+    Basic,
+    Indexed,
+    TrueColor,
+}
+
+/// The channel values of the 6×6×6 color cube in the 256-color palette.
+const CUBE_LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+
+impl Color {
+    /// The color's RGB value, using [`PALETTE`] for the basic colors, or
+    /// `None` for [`Color::Default`].
+    pub fn rgb(self) -> Option<[u8; 3]> {
+        match self {
+            Self::Default => None,
+            Self::Basic(n) => Some(PALETTE[n as usize % 16]),
+            Self::Indexed(n) => Some(indexed_rgb(n)),
+            Self::Rgb(r, g, b) => Some([r, g, b]),
+        }
+    }
+
+    /// The closest color that a terminal with the given support can show.
+    pub fn downgrade(self, support: ColorSupport) -> Self {
+        match (self, support) {
+            (Self::Default | Self::Basic(_), _) => self,
+            (Self::Indexed(n), _) if n < 16 => Self::Basic(n),
+            (Self::Indexed(_), ColorSupport::Indexed | ColorSupport::TrueColor) => self,
+            (Self::Rgb(..), ColorSupport::TrueColor) => self,
+            (Self::Rgb(r, g, b), ColorSupport::Indexed) => {
+                Self::Indexed(nearest_indexed([r, g, b]))
+            }
+            (Self::Indexed(_) | Self::Rgb(..), ColorSupport::Basic) => {
+                let rgb = self.rgb().unwrap();
+                Self::Basic(nearest(rgb, (0..16).map(|n| (n, PALETTE[n as usize]))))
+            }
+        }
+    }
+
+    /// The parameters for selecting this color with an SGR sequence.
+    pub(crate) fn sgr_params(self, background: bool) -> String {
+        let base = if background { 40 } else { 30 };
+        match self {
+            Self::Default => format!("{}", base + 9),
+            Self::Basic(n) if n % 16 < 8 => format!("{}", base + n % 16),
+            Self::Basic(n) => format!("{}", base + 60 + n % 8),
+            Self::Indexed(n) => format!("{};5;{n}", base + 8),
+            Self::Rgb(r, g, b) => format!("{};2;{r};{g};{b}", base + 8),
+        }
+    }
+}
+
+impl ColorSupport {
+    /// Detects the terminal's color support from the `COLORTERM` and `TERM`
+    /// environment variables.
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    /// Color support given the values of `COLORTERM` and `TERM`.
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        let term = term.unwrap_or("");
+        if matches!(colorterm, Some("truecolor" | "24bit")) || term.ends_with("-direct") {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Indexed
+        } else {
+            Self::Basic
+        }
+    }
+}
+
+fn indexed_rgb(n: u8) -> [u8; 3] {
+    match n {
+        0..16 => PALETTE[n as usize],
+        16..232 => {
+            let n = n - 16;
+            [n / 36, n / 6 % 6, n % 6].map(|level| CUBE_LEVELS[level as usize])
+        }
+        232.. => [8 + 10 * (n - 232); 3],
+    }
+}
+
+/// The closest color in the 256-color palette, not counting the basic colors
+/// whose shades vary between terminals.
+fn nearest_indexed(rgb: [u8; 3]) -> u8 {
+    nearest(rgb, (16..=255).map(|n| (n, indexed_rgb(n))))
+}
+
+fn nearest(rgb: [u8; 3], candidates: impl Iterator<Item = (u8, [u8; 3])>) -> u8 {
+    let distance = |other: [u8; 3]| -> u32 {
+        rgb.iter()
+            .zip(other)
+            .map(|(&a, b)| (a as i32 - b as i32).pow(2) as u32)
+            .sum()
+    };
+    candidates
+        .min_by_key(|&(_, other)| distance(other))
+        .map(|(n, _)| n)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::{Color, ColorSupport};
+
+    #[test]
+    fn test_sgr_params() {
+        assert_eq!(Color::Default.sgr_params(true), "49");
+        assert_eq!(Color::Basic(2).sgr_params(false), "32");
+        assert_eq!(Color::Basic(12).sgr_params(true), "104");
+        assert_eq!(Color::Indexed(200).sgr_params(false), "38;5;200");
+        assert_eq!(Color::Rgb(1, 2, 3).sgr_params(true), "48;2;1;2;3");
+    }
+
+    #[test]
+    fn test_detect() {
+        use ColorSupport::*;
+        assert_eq!(ColorSupport::from_env(None, None), Basic);
+        assert_eq!(ColorSupport::from_env(None, Some("xterm")), Basic);
+        assert_eq!(
+            ColorSupport::from_env(None, Some("xterm-256color")),
+            Indexed
+        );
+        assert_eq!(
+            ColorSupport::from_env(Some("truecolor"), Some("xterm")),
+            TrueColor
+        );
+        assert_eq!(ColorSupport::from_env(Some("24bit"), None), TrueColor);
+        assert_eq!(
+            ColorSupport::from_env(None, Some("xterm-direct")),
+            TrueColor
+        );
+    }
+
+    #[test]
+    fn test_downgrade() {
+        let orange = Color::Rgb(0xff, 0x80, 0x00);
+        assert_eq!(orange.downgrade(ColorSupport::TrueColor), orange);
+        assert_eq!(orange.downgrade(ColorSupport::Indexed), Color::Indexed(208));
+        assert_eq!(orange.downgrade(ColorSupport::Basic), Color::Basic(3));
+
+        let grey = Color::Rgb(0x80, 0x80, 0x80);
+        assert_eq!(grey.downgrade(ColorSupport::Indexed), Color::Indexed(244));
+        assert_eq!(
+            Color::Indexed(196).downgrade(ColorSupport::Basic),
+            Color::Basic(9)
+        );
+        assert_eq!(
+            Color::Indexed(3).downgrade(ColorSupport::Indexed),
+            Color::Basic(3)
+        );
+        assert_eq!(
+            Color::Default.downgrade(ColorSupport::Basic),
+            Color::Default
+        );
+    }
+}
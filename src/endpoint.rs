@@ -0,0 +1,154 @@
+use rand::Rng;
+
+use crate::{
+    hex::{Position, Vector},
+    maze::Maze,
+    solver,
+};
+
+/// Where to put the start or goal of a maze.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endpoint {
+    /// A particular cell. Cells outside the maze are moved to its nearest
+    /// edge.
+    Cell(Position),
+    /// A cell picked at random.
+    Random,
+    /// The cell farthest from the other endpoint.
+    ///
+    /// When both endpoints are farthest, they are found with two
+    /// breadth-first sweeps: the start is the cell farthest from the
+    /// top-left corner, and the goal is the cell farthest from the start. In
+    /// a perfect maze, this gives the longest route there is.
+    Farthest,
+}
+
+impl Endpoint {
+    pub const TOP_LEFT: Self = Self::Cell(Position { col: 0, row: 0 });
+    /// The bottom-right cell of any maze.
+    pub const BOTTOM_RIGHT: Self = Self::Cell(Position {
+        col: i16::MAX,
+        row: i16::MAX,
+    });
+
+    /// The cell of a maze of the given size, if this is a particular cell and
+    /// the maze has any cells.
+    pub fn cell(self, cols: u16, rows: u16) -> Option<Vector> {
+        let Self::Cell(position) = self else {
+            return None;
+        };
+        if cols == 0 || rows == 0 {
+            return None;
+        }
+        Some(Vector::from(Position {
+            col: position.col.clamp(0, cols as i16 - 1),
+            row: position.row.clamp(0, rows as i16 - 1),
+        }))
+    }
+}
+
+/// Places the start and goal in a maze with at least one cell.
+pub fn place(maze: &Maze, start: Endpoint, goal: Endpoint, rng: &mut impl Rng) -> (Vector, Vector) {
+    let farthest = |from: Vector| solver::farthest(maze, from).map_or(from, |(pos, _)| pos);
+    let (cols, rows) = (maze.cells.cols(), maze.cells.rows());
+    let mut place_one = |endpoint: Endpoint, other: Option<Vector>| match endpoint {
+        Endpoint::Cell(_) => endpoint.cell(cols, rows).unwrap(),
+        Endpoint::Random => Vector::from(Position {
+            col: rng.gen_range(0..cols as i16),
+            row: rng.gen_range(0..rows as i16),
+        }),
+        Endpoint::Farthest => farthest(other.unwrap_or(Vector::ZERO)),
+    };
+
+    match (start, goal) {
+        (Endpoint::Farthest, Endpoint::Farthest) => {
+            let start = place_one(start, None);
+            (start, farthest(start))
+        }
+        (Endpoint::Farthest, _) => {
+            let goal = place_one(goal, None);
+            (place_one(start, Some(goal)), goal)
+        }
+        _ => {
+            let start = place_one(start, None);
+            (start, place_one(goal, Some(start)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::{
+        endpoint::{place, Endpoint},
+        generator::{Algorithm, SeededRng},
+        hex::{Position, Vector},
+        maze::{analysis::Analysis, Maze},
+        solver,
+    };
+
+    fn generate(cols: u16, rows: u16) -> Maze {
+        Algorithm::Prim.generator(cols, rows, 4).finish().clone()
+    }
+
+    #[test]
+    fn test_cells() {
+        let maze = Maze::with_size(4, 3);
+        let mut rng = SeededRng::seed_from_u64(0);
+        assert_eq!(
+            place(&maze, Endpoint::TOP_LEFT, Endpoint::BOTTOM_RIGHT, &mut rng),
+            (Vector::ZERO, Vector::from(Position { col: 3, row: 2 })),
+        );
+        let start = Endpoint::Cell(Position { col: -2, row: 1 });
+        assert_eq!(
+            place(&maze, start, Endpoint::TOP_LEFT, &mut rng).0,
+            Vector::from(Position { col: 0, row: 1 }),
+        );
+    }
+
+    #[test]
+    fn test_random() {
+        let maze = Maze::with_size(4, 3);
+        let placed: Vec<_> = (0..20)
+            .map(|seed| {
+                let mut rng = SeededRng::seed_from_u64(seed);
+                place(&maze, Endpoint::Random, Endpoint::Random, &mut rng)
+            })
+            .collect();
+        assert!(placed
+            .iter()
+            .all(|&(start, goal)| maze.cells.contains(start) && maze.cells.contains(goal)));
+        assert!(placed.iter().any(|&other| other != placed[0]));
+
+        let mut rng = SeededRng::seed_from_u64(3);
+        assert_eq!(
+            place(&maze, Endpoint::Random, Endpoint::Random, &mut rng),
+            placed[3],
+        );
+    }
+
+    #[test]
+    fn test_farthest() {
+        let maze = generate(12, 8);
+        let mut rng = SeededRng::seed_from_u64(0);
+
+        // Two sweeps find the ends of a longest route through a tree.
+        let (start, goal) = place(&maze, Endpoint::Farthest, Endpoint::Farthest, &mut rng);
+        let moves = solver::distances(&maze, start)[goal].unwrap();
+        assert_eq!(moves as usize, Analysis::new(&maze).diameter);
+
+        let (start, goal) = place(&maze, Endpoint::TOP_LEFT, Endpoint::Farthest, &mut rng);
+        assert_eq!(start, Vector::ZERO);
+        assert_eq!(
+            Some(goal),
+            solver::farthest(&maze, start).map(|(pos, _)| pos)
+        );
+
+        let (start, goal) = place(&maze, Endpoint::Farthest, Endpoint::BOTTOM_RIGHT, &mut rng);
+        assert_eq!(
+            Some(start),
+            solver::farthest(&maze, goal).map(|(pos, _)| pos)
+        );
+    }
+}
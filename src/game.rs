@@ -0,0 +1,210 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    hex::Vector,
+    maze::Maze,
+    solver::{BfsSolver, MazeSolver},
+    terminal::{BLUE, GREEN, LIGHT_GREY, RED, YELLOW},
+};
+
+/// A player walking through a maze from a start cell to a goal cell.
+pub struct Game {
+    maze: Maze,
+    player: Vector,
+    goal: Vector,
+    moves: u32,
+    started: Instant,
+    finished: Option<Duration>,
+    visited: Vec<Vector>,
+    solution: Vec<Vector>,
+    show_solution: bool,
+}
+
+impl Game {
+    pub fn new(maze: Maze, start: Vector, goal: Vector) -> Self {
+        // Moves are what the player is scored on, so terrain is ignored.
+        let mut solver = BfsSolver::new(maze.clone(), start, goal);
+        while !solver.is_done() {
+            solver.step();
+        }
+
+        let mut game = Self {
+            maze,
+            player: start,
+            goal,
+            moves: 0,
+            started: Instant::now(),
+            // Starting on the goal is winning straight away.
+            finished: (start == goal).then_some(Duration::ZERO),
+            visited: vec![start],
+            solution: solver.path().to_vec(),
+            show_solution: false,
+        };
+        game.paint();
+        game
+    }
+
+    /// The maze, with backgrounds showing the player, goal and the cells
+    /// visited so far.
+    pub fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    pub fn player(&self) -> Vector {
+        self.player
+    }
+
+    pub fn moves(&self) -> u32 {
+        self.moves
+    }
+
+    /// The number of moves in the shortest route from start to goal, however
+    /// slow its terrain is to cross.
+    pub fn optimal_moves(&self) -> u32 {
+        self.solution.len().saturating_sub(1) as u32
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.finished.is_some()
+    }
+
+    /// Time since the game started, stopping once it is won.
+    pub fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(|| self.started.elapsed())
+    }
+
+    /// Moves the player one cell in `dir`, one of [`Vector::DIRECTIONS`].
+    /// Returns false if a wall is in the way or the game is already won.
+    pub fn try_move(&mut self, dir: Vector) -> bool {
+        let next = self.player + dir;
+        if self.is_won()
+            || !Vector::DIRECTIONS.contains(&dir)
+            || self.maze.wall_between(self.player, next)
+        {
+            return false;
+        }
+
+        self.player = next;
+        self.moves += 1;
+        if !self.visited.contains(&next) {
+            self.visited.push(next);
+        }
+        if self.player == self.goal {
+            self.finished = Some(self.started.elapsed());
+        }
+        self.paint();
+        true
+    }
+
+    /// Shows or hides the shortest route from start to goal.
+    pub fn toggle_solution(&mut self) {
+        self.show_solution = !self.show_solution;
+        self.paint();
+    }
+
+    fn paint(&mut self) {
+        self.maze.clear_backgrounds();
+        for &pos in &self.visited {
+            self.maze.cells[pos].background = LIGHT_GREY;
+        }
+        if self.show_solution {
+            for &pos in &self.solution {
+                self.maze.cells[pos].background = GREEN;
+            }
+        }
+        self.maze.cells[self.goal].background = RED;
+        self.maze.cells[self.player].background = if self.is_won() { YELLOW } else { BLUE };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rand::SeedableRng;
+
+    use crate::{
+        game::Game,
+        generator::{Algorithm, BraidGenerator, MazeGenerator, SeededRng},
+        hex::{Position, Vector},
+        maze::{
+            fixtures::{corridor, BOTTOM},
+            Maze,
+        },
+        solver::distances,
+        terminal::GREEN,
+        terrain::{self, Terrain},
+    };
+
+    #[test]
+    fn test_walls_block_movement() {
+        let mut game = Game::new(corridor(), Vector::ZERO, BOTTOM);
+        assert!(!game.try_move(Vector::NORTH));
+        assert!(!game.try_move(Vector::SOUTH_EAST));
+        assert!(!game.try_move(Vector::TWO_EAST));
+        assert_eq!(game.player(), Vector::ZERO);
+        assert_eq!(game.moves(), 0);
+    }
+
+    #[test]
+    fn test_reach_goal() {
+        let mut game = Game::new(corridor(), Vector::ZERO, BOTTOM);
+        assert_eq!(game.optimal_moves(), 2);
+
+        assert!(game.try_move(Vector::SOUTH));
+        assert!(game.try_move(Vector::NORTH));
+        assert!(game.try_move(Vector::SOUTH));
+        assert!(!game.is_won());
+        assert!(game.try_move(Vector::SOUTH));
+        assert!(game.is_won());
+        assert_eq!(game.moves(), 4);
+
+        assert!(!game.try_move(Vector::NORTH));
+        assert_eq!(game.elapsed(), game.elapsed());
+    }
+
+    #[test]
+    fn test_toggle_solution() {
+        let mut game = Game::new(corridor(), Vector::ZERO, BOTTOM);
+        assert_ne!(game.maze().cells[Vector::SOUTH].background, GREEN);
+        game.toggle_solution();
+        assert_eq!(game.maze().cells[Vector::SOUTH].background, GREEN);
+        game.toggle_solution();
+        assert_ne!(game.maze().cells[Vector::SOUTH].background, GREEN);
+    }
+
+    #[test]
+    fn test_optimal_ignores_terrain() {
+        let goal = Vector::from(Position { col: 19, row: 9 });
+        for seed in 0..10 {
+            let generator = Algorithm::Dfs.generator(20, 10, seed);
+            let mut maze = BraidGenerator::with_seed(generator, 1.0, seed)
+                .finish()
+                .clone();
+            terrain::scatter(&mut maze, &mut SeededRng::seed_from_u64(seed));
+
+            let moves = distances(&maze, Vector::ZERO)[goal].unwrap();
+            let game = Game::new(maze, Vector::ZERO, goal);
+            assert_eq!(game.optimal_moves(), moves, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_terrain_shown() {
+        let mut maze = corridor();
+        maze.terrain[Position { col: 1, row: 0 }] = Terrain::Water;
+        let game = Game::new(maze, Vector::ZERO, BOTTOM);
+        assert_eq!(
+            game.maze().cells[Position { col: 1, row: 0 }].background,
+            Terrain::Water.background()
+        );
+    }
+
+    #[test]
+    fn test_start_on_goal() {
+        let game = Game::new(Maze::with_size(1, 1), Vector::ZERO, Vector::ZERO);
+        assert!(game.is_won());
+        assert_eq!(game.optimal_moves(), 0);
+        assert_eq!(game.elapsed(), Duration::ZERO);
+    }
+}
@@ -1,72 +1,275 @@
-use rand::Rng;
-
-use crate::{
-    grid::HexGrid,
-    hex::Vector,
-    maze::Maze,
-    terminal::{Terminal, BLUE, CLEAR_COLOR, LIGHT_GREY},
-};
-
-/// Uses randomized DFS to generate a maze.
-pub struct Generator {
-    pub maze: Maze,
-    pub is_done: bool,
-    head: Vector,
-    tail: Vec<Vector>,
-    visited: HexGrid<bool>,
+use std::{fmt, str::FromStr};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::{hex::Vector, maze::Maze};
+
+pub mod braid;
+pub mod dfs;
+pub mod prim;
+
+pub use braid::BraidGenerator;
+pub use dfs::DfsGenerator;
+pub use prim::PrimGenerator;
+
+/// The random number generator behind everything made from a seed.
+///
+/// Unlike `StdRng`, its output is fixed across versions of `rand` and across
+/// platforms, so a seed always gives the same maze.
+pub type SeededRng = ChaCha8Rng;
+
+/// The separate streams of [`SeededRng`] drawn from for each thing made from
+/// one seed, so that none of them repeats another's random choices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    /// The maze made by [`Algorithm::generator`].
+    Generator,
+    /// The walls knocked out by [`BraidGenerator::with_seed`].
+    Braid,
+    /// Where random endpoints are placed.
+    Endpoints,
+    /// The terrain laid by [`terrain::scatter`](crate::terrain::scatter).
+    Terrain,
 }
 
-impl Generator {
-    pub fn new(term: &Terminal) -> Self {
-        let maze = Maze::new(term);
-        let head = Vector {
-            col: 0,
-            half_row: 0,
-        };
-        let tail = Vec::new();
-        let visited = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| false);
-        Self {
-            maze,
-            is_done: false,
-            head,
-            tail,
-            visited,
-        }
+impl Stream {
+    pub fn rng(self, seed: u64) -> SeededRng {
+        let mut rng = SeededRng::seed_from_u64(seed);
+        rng.set_stream(self as u64);
+        rng
     }
+}
 
-    pub fn step(&mut self) {
-        self.visited[self.head] = true;
-        if let Some(next) = self.pick_next_cell() {
-            self.maze.set_wall_between(self.head, next, false);
-            self.tail.push(self.head);
-            self.head = next;
-        } else if let Some(prev) = self.tail.pop() {
-            self.head = prev;
-        } else {
-            self.is_done = true;
+/// A maze generation algorithm that can be run one step at a time.
+pub trait MazeGenerator {
+    /// The maze as generated so far, with backgrounds showing the
+    /// algorithm's progress.
+    fn maze(&self) -> &Maze;
+
+    /// Advances the algorithm by one step. Does nothing once done.
+    fn step(&mut self);
+
+    fn is_done(&self) -> bool;
+
+    /// Runs the algorithm to the end, returning the finished maze.
+    fn finish(&mut self) -> &Maze {
+        while !self.is_done() {
+            self.step();
         }
+        self.maze()
+    }
+}
+
+/// The available generation algorithms, for selecting one at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    #[default]
+    Dfs,
+    Prim,
+}
+
+impl Algorithm {
+    pub const ALL: [Self; 2] = [Self::Dfs, Self::Prim];
 
-        for pos in self.maze.cells.indices() {
-            self.maze.cells[pos].background = CLEAR_COLOR;
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dfs => "dfs",
+            Self::Prim => "prim",
         }
-        for &pos in &self.tail {
-            self.maze.cells[pos].background = LIGHT_GREY;
+    }
+
+    /// The algorithm after this one, wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&a| a == self).unwrap();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Creates a generator whose random choices are fully determined by
+    /// `seed`, so the same seed and size always yield the same maze.
+    pub fn generator(self, cols: u16, rows: u16, seed: u64) -> Box<dyn MazeGenerator> {
+        self.generator_from(cols, rows, seed, Vector::ZERO)
+    }
+
+    /// Like [`Algorithm::generator`], but starting from the given cell
+    /// instead of the top-left corner.
+    pub fn generator_from(
+        self,
+        cols: u16,
+        rows: u16,
+        seed: u64,
+        start: Vector,
+    ) -> Box<dyn MazeGenerator> {
+        let rng = Stream::Generator.rng(seed);
+        match self {
+            Self::Dfs => Box::new(DfsGenerator::with_start(cols, rows, start, rng)),
+            Self::Prim => Box::new(PrimGenerator::with_start(cols, rows, start, rng)),
         }
-        self.maze.cells[self.head].background = BLUE;
     }
+}
 
-    pub fn pick_next_cell(&self) -> Option<Vector> {
-        let candidates = Vector::DIRECTIONS
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
             .into_iter()
-            .map(|dir| dir + self.head)
-            .filter(|&neighbor| self.maze.cells.contains(neighbor) && !self.visited[neighbor])
-            .collect::<Vec<_>>();
-        if candidates.is_empty() {
-            None
-        } else {
-            candidates
-                .get(rand::thread_rng().gen_range(0..candidates.len()))
-                .copied()
+            .find(|a| a.name() == s)
+            .ok_or_else(|| format!("unknown generator {s:?}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use rand::RngCore;
+
+    use crate::{
+        generator::{Algorithm, Stream},
+        hex::{Position, Vector},
+        maze::Maze,
+    };
+
+    fn generate(algorithm: Algorithm, cols: u16, rows: u16, seed: u64) -> Maze {
+        algorithm.generator(cols, rows, seed).finish().clone()
+    }
+
+    fn open_walls(maze: &Maze) -> usize {
+        maze.cells
+            .indices()
+            .map(Vector::from)
+            .flat_map(|pos| Vector::DIRECTIONS.map(|dir| (pos, pos + dir)))
+            .filter(|&(a, b)| maze.cells.contains(b) && !maze.wall_between(a, b))
+            .count()
+            / 2
+    }
+
+    #[test]
+    fn test_next() {
+        assert_eq!(Algorithm::Dfs.next(), Algorithm::Prim);
+        assert_eq!(Algorithm::Prim.next(), Algorithm::Dfs);
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(algorithm.to_string().parse(), Ok(algorithm));
+        }
+        assert!("kruskal".parse::<Algorithm>().is_err());
+    }
+
+    #[test]
+    fn test_same_seed_same_maze() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(
+                generate(algorithm, 12, 8, 42),
+                generate(algorithm, 12, 8, 42)
+            );
+        }
+    }
+
+    #[test]
+    fn test_streams() {
+        let streams = [
+            Stream::Generator,
+            Stream::Braid,
+            Stream::Endpoints,
+            Stream::Terrain,
+        ];
+        let draws: Vec<_> = streams.map(|stream| stream.rng(42).next_u64()).into();
+        for (i, draw) in draws.iter().enumerate() {
+            assert!(!draws[..i].contains(draw), "{:?}", streams[i]);
+        }
+        assert_eq!(Stream::Endpoints.rng(42).next_u64(), draws[2]);
+    }
+
+    #[test]
+    fn test_different_seed_different_maze() {
+        for algorithm in Algorithm::ALL {
+            assert_ne!(generate(algorithm, 12, 8, 1), generate(algorithm, 12, 8, 2));
+        }
+    }
+
+    #[test]
+    fn test_generates_spanning_tree() {
+        for algorithm in Algorithm::ALL {
+            let maze = generate(algorithm, 9, 5, 7);
+            assert_eq!(open_walls(&maze), 9 * 5 - 1);
+        }
+    }
+
+    #[test]
+    fn test_start() {
+        let start = Vector::from(Position { col: 4, row: 2 });
+        for algorithm in Algorithm::ALL {
+            let mut generator = algorithm.generator_from(9, 5, 7, start);
+            generator.step();
+            // The first passage leads out of the start.
+            assert_eq!(generator.maze().open_neighbors(start).count(), 1);
+
+            assert!(generator.finish().is_perfect());
+            assert_ne!(
+                generator.maze().to_ascii(),
+                generate(algorithm, 9, 5, 7).to_ascii()
+            );
+        }
+    }
+
+    #[test]
+    fn test_pinned_mazes() {
+        // These only change if the generators or the random number generator
+        // do, which would change every maze for a given seed.
+        assert_eq!(
+            generate(Algorithm::Dfs, 5, 3, 1).to_ascii(),
+            r#" ___     ___     ___
+/   \___/   \___/   \
+\___    \   /    ___/
+/   \   /    ___    \
+\   /   \___/   \   /
+/   \___        /   \
+\___     ___/    ___/
+    \___/   \___/
+"#
+        );
+        assert_eq!(
+            generate(Algorithm::Prim, 5, 3, 1).to_ascii(),
+            r#" ___     ___     ___
+/   \___/   \___/   \
+\___                /
+/   \   /   \___/   \
+\___    \___    \   /
+/    ___    \___/   \
+\___/    ___    \___/
+    \___/   \___/
+"#
+        );
+    }
+
+    #[test]
+    fn test_empty_maze() {
+        for algorithm in Algorithm::ALL {
+            let generator = algorithm.generator(0, 0, 0);
+            assert!(generator.is_done());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_generates_perfect_maze(
+            algorithm in proptest::sample::select(Algorithm::ALL.to_vec()),
+            cols in 1..30u16,
+            rows in 1..20u16,
+            seed: u64,
+        ) {
+            let maze = generate(algorithm, cols, rows, seed);
+            prop_assert!(maze.is_perfect());
+            prop_assert!(maze.unreachable_from(Vector::ZERO).is_empty());
         }
     }
 }
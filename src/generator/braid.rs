@@ -0,0 +1,221 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    generator::{MazeGenerator, SeededRng, Stream},
+    hex::Vector,
+    maze::Maze,
+    terminal::{BLUE, CLEAR_COLOR},
+};
+
+/// Removes dead ends from the maze made by another generator, one per step,
+/// by knocking out one of their walls. The result is a braid maze with loops
+/// and more than one route between cells, which can't be solved by following
+/// a wall.
+///
+/// Where it can, a dead end is joined to a neighboring dead end, removing
+/// both at once.
+pub struct BraidGenerator<R = SeededRng> {
+    generator: Box<dyn MazeGenerator>,
+    rng: R,
+    fraction: f64,
+    braiding: Option<Braiding>,
+}
+
+struct Braiding {
+    maze: Maze,
+    /// Dead ends not yet considered, in the order they will be visited from
+    /// the back.
+    dead_ends: Vec<Vector>,
+    /// How many more dead ends to remove.
+    remaining: usize,
+}
+
+impl BraidGenerator {
+    /// Like [`BraidGenerator::new`], but with random choices determined by
+    /// `seed`, drawn from their own [`Stream`].
+    pub fn with_seed(generator: Box<dyn MazeGenerator>, fraction: f64, seed: u64) -> Self {
+        Self::new(generator, fraction, Stream::Braid.rng(seed))
+    }
+}
+
+impl<R: Rng> BraidGenerator<R> {
+    /// Braids the maze from `generator`, removing `fraction` of its dead
+    /// ends, between 0 and 1.
+    pub fn new(generator: Box<dyn MazeGenerator>, fraction: f64, rng: R) -> Self {
+        let mut braid = Self {
+            generator,
+            rng,
+            fraction: fraction.clamp(0.0, 1.0),
+            braiding: None,
+        };
+        braid.start_if_generated();
+        braid
+    }
+
+    fn start_if_generated(&mut self) {
+        if self.braiding.is_some() || !self.generator.is_done() {
+            return;
+        }
+
+        let mut maze = self.generator.maze().clone();
+        for pos in maze.cells.indices() {
+            maze.cells[pos].background = CLEAR_COLOR;
+        }
+        let mut dead_ends: Vec<_> = maze
+            .cells
+            .indices()
+            .map(Vector::from)
+            .filter(|&pos| maze.degree(pos) == 1)
+            .collect();
+        dead_ends.shuffle(&mut self.rng);
+        let remaining = (self.fraction * dead_ends.len() as f64).round() as usize;
+        self.braiding = Some(Braiding {
+            maze,
+            dead_ends,
+            remaining,
+        });
+    }
+}
+
+impl<R: Rng> MazeGenerator for BraidGenerator<R> {
+    fn maze(&self) -> &Maze {
+        match &self.braiding {
+            Some(braiding) => &braiding.maze,
+            None => self.generator.maze(),
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.braiding
+            .as_ref()
+            .is_some_and(|braiding| braiding.remaining == 0)
+    }
+
+    fn step(&mut self) {
+        let Some(braiding) = &mut self.braiding else {
+            self.generator.step();
+            self.start_if_generated();
+            return;
+        };
+        if braiding.remaining == 0 {
+            return;
+        }
+
+        let maze = &mut braiding.maze;
+        for pos in maze.cells.indices() {
+            maze.cells[pos].background = CLEAR_COLOR;
+        }
+
+        // Earlier steps may have already joined some of the dead ends.
+        while let Some(pos) = braiding.dead_ends.pop() {
+            if maze.degree(pos) != 1 {
+                continue;
+            }
+
+            let walled: Vec<_> = Vector::DIRECTIONS
+                .into_iter()
+                .map(|dir| pos + dir)
+                .filter(|&neighbor| {
+                    maze.cells.contains(neighbor) && maze.wall_between(pos, neighbor)
+                })
+                .collect();
+            let dead_ends: Vec<_> = walled
+                .iter()
+                .copied()
+                .filter(|&neighbor| maze.degree(neighbor) == 1)
+                .collect();
+            let candidates = if dead_ends.is_empty() {
+                walled
+            } else {
+                dead_ends
+            };
+            let Some(&neighbor) = candidates.choose(&mut self.rng) else {
+                continue;
+            };
+
+            let removed = if maze.degree(neighbor) == 1 { 2 } else { 1 };
+            maze.set_wall_between(pos, neighbor, false);
+            braiding.remaining = braiding.remaining.saturating_sub(removed);
+            maze.cells[pos].background = BLUE;
+            maze.cells[neighbor].background = BLUE;
+            break;
+        }
+
+        if braiding.dead_ends.is_empty() {
+            braiding.remaining = 0;
+        }
+        if braiding.remaining == 0 {
+            for pos in maze.cells.indices() {
+                maze.cells[pos].background = CLEAR_COLOR;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{RngCore, SeedableRng};
+
+    use crate::{
+        generator::{Algorithm, BraidGenerator, MazeGenerator, SeededRng},
+        maze::{analysis::Analysis, Maze},
+    };
+
+    fn braid(algorithm: Algorithm, fraction: f64, seed: u64) -> (Maze, Maze) {
+        let perfect = algorithm.generator(12, 8, seed).finish().clone();
+
+        let generator = algorithm.generator(12, 8, seed);
+        let mut braid = BraidGenerator::with_seed(generator, fraction, seed);
+        (perfect, braid.finish().clone())
+    }
+
+    #[test]
+    fn test_braid_all() {
+        for algorithm in Algorithm::ALL {
+            let (perfect, braided) = braid(algorithm, 1.0, 5);
+            assert!(Analysis::new(&perfect).dead_ends() > 0);
+            assert_eq!(Analysis::new(&braided).dead_ends(), 0);
+            assert!(braided.is_connected());
+            assert!(braided.has_cycle());
+        }
+    }
+
+    #[test]
+    fn test_braid_fraction() {
+        for algorithm in Algorithm::ALL {
+            let (perfect, braided) = braid(algorithm, 0.5, 5);
+            let before = Analysis::new(&perfect).dead_ends();
+            let after = Analysis::new(&braided).dead_ends();
+            // Joining two dead ends can remove one more than asked for.
+            let removed = before - after;
+            let target = (before as f64 / 2.0).round() as usize;
+            assert!(removed == target || removed == target + 1);
+
+            // Each removed wall either joins two dead ends or one dead end to
+            // a passage.
+            assert!(braided.cycles() <= removed);
+            assert!(braided.cycles() * 2 >= removed);
+        }
+    }
+
+    #[test]
+    fn test_braid_none() {
+        let (perfect, braided) = braid(Algorithm::Dfs, 0.0, 5);
+        assert_eq!(perfect.to_ascii(), braided.to_ascii());
+    }
+
+    #[test]
+    fn test_braid_empty() {
+        let generator = Algorithm::Dfs.generator(0, 0, 0);
+        let braid = BraidGenerator::new(generator, 1.0, SeededRng::seed_from_u64(0));
+        assert!(braid.is_done());
+    }
+
+    #[test]
+    fn test_with_seed_own_stream() {
+        let generator = Algorithm::Dfs.generator(0, 0, 7);
+        let mut braid = BraidGenerator::with_seed(generator, 1.0, 7);
+        let mut maze_rng = SeededRng::seed_from_u64(7);
+        assert_ne!(braid.rng.next_u64(), maze_rng.next_u64());
+    }
+}
@@ -0,0 +1,94 @@
+use rand::Rng;
+
+use crate::{
+    generator::{MazeGenerator, SeededRng},
+    grid::HexGrid,
+    hex::Vector,
+    maze::Maze,
+    terminal::{BLUE, CLEAR_COLOR, LIGHT_GREY},
+};
+
+/// Uses randomized DFS to generate a maze.
+///
+/// Tends to produce long, winding corridors with few dead ends.
+pub struct DfsGenerator<R = SeededRng> {
+    maze: Maze,
+    rng: R,
+    is_done: bool,
+    head: Vector,
+    tail: Vec<Vector>,
+    visited: HexGrid<bool>,
+}
+
+impl<R: Rng> DfsGenerator<R> {
+    pub fn new(cols: u16, rows: u16, rng: R) -> Self {
+        Self::with_start(cols, rows, Vector::ZERO, rng)
+    }
+
+    /// Generates a maze by walking from `start`, which must be in the maze
+    /// unless it has no cells.
+    pub fn with_start(cols: u16, rows: u16, start: Vector, rng: R) -> Self {
+        let maze = Maze::with_size(cols, rows);
+        let head = start;
+        let tail = Vec::new();
+        let visited = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| false);
+        Self {
+            is_done: maze.cells.is_empty(),
+            maze,
+            rng,
+            head,
+            tail,
+            visited,
+        }
+    }
+
+    fn pick_next_cell(&mut self) -> Option<Vector> {
+        let candidates = Vector::DIRECTIONS
+            .into_iter()
+            .map(|dir| dir + self.head)
+            .filter(|&neighbor| self.maze.cells.contains(neighbor) && !self.visited[neighbor])
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            None
+        } else {
+            candidates
+                .get(self.rng.gen_range(0..candidates.len()))
+                .copied()
+        }
+    }
+}
+
+impl<R: Rng> MazeGenerator for DfsGenerator<R> {
+    fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn step(&mut self) {
+        if self.is_done {
+            return;
+        }
+
+        self.visited[self.head] = true;
+        if let Some(next) = self.pick_next_cell() {
+            self.maze.set_wall_between(self.head, next, false);
+            self.tail.push(self.head);
+            self.head = next;
+        } else if let Some(prev) = self.tail.pop() {
+            self.head = prev;
+        } else {
+            self.is_done = true;
+        }
+
+        for pos in self.maze.cells.indices() {
+            self.maze.cells[pos].background = CLEAR_COLOR;
+        }
+        for &pos in &self.tail {
+            self.maze.cells[pos].background = LIGHT_GREY;
+        }
+        self.maze.cells[self.head].background = BLUE;
+    }
+}
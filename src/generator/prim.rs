@@ -0,0 +1,115 @@
+use rand::Rng;
+
+use crate::{
+    generator::{MazeGenerator, SeededRng},
+    grid::HexGrid,
+    hex::Vector,
+    maze::Maze,
+    terminal::{BLUE, CLEAR_COLOR, LIGHT_GREY},
+};
+
+/// Uses randomized Prim's algorithm to generate a maze.
+///
+/// Tends to produce many short dead ends branching off a central area.
+pub struct PrimGenerator<R = SeededRng> {
+    maze: Maze,
+    rng: R,
+    is_done: bool,
+    head: Vector,
+    frontier: Vec<Vector>,
+    in_maze: HexGrid<bool>,
+    in_frontier: HexGrid<bool>,
+}
+
+impl<R: Rng> PrimGenerator<R> {
+    pub fn new(cols: u16, rows: u16, rng: R) -> Self {
+        Self::with_start(cols, rows, Vector::ZERO, rng)
+    }
+
+    /// Grows a maze outwards from `start`, which must be in the maze unless
+    /// it has no cells.
+    pub fn with_start(cols: u16, rows: u16, start: Vector, rng: R) -> Self {
+        let maze = Maze::with_size(cols, rows);
+        let in_maze = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| false);
+        let in_frontier = in_maze.clone();
+        let mut generator = Self {
+            is_done: maze.cells.is_empty(),
+            maze,
+            rng,
+            head: start,
+            frontier: Vec::new(),
+            in_maze,
+            in_frontier,
+        };
+        if !generator.is_done {
+            generator.add_to_maze(start);
+        }
+        generator
+    }
+
+    fn add_to_maze(&mut self, cell: Vector) {
+        self.in_maze[cell] = true;
+        self.head = cell;
+        for dir in Vector::DIRECTIONS {
+            let neighbor = cell + dir;
+            if self.maze.cells.contains(neighbor)
+                && !self.in_maze[neighbor]
+                && !self.in_frontier[neighbor]
+            {
+                self.in_frontier[neighbor] = true;
+                self.frontier.push(neighbor);
+            }
+        }
+    }
+
+    fn pick_connection(&mut self, cell: Vector) -> Option<Vector> {
+        let candidates = Vector::DIRECTIONS
+            .into_iter()
+            .map(|dir| dir + cell)
+            .filter(|&neighbor| self.maze.cells.contains(neighbor) && self.in_maze[neighbor])
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            None
+        } else {
+            candidates
+                .get(self.rng.gen_range(0..candidates.len()))
+                .copied()
+        }
+    }
+}
+
+impl<R: Rng> MazeGenerator for PrimGenerator<R> {
+    fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn step(&mut self) {
+        if self.is_done {
+            return;
+        }
+
+        if self.frontier.is_empty() {
+            self.is_done = true;
+        } else {
+            let index = self.rng.gen_range(0..self.frontier.len());
+            let cell = self.frontier.swap_remove(index);
+            self.in_frontier[cell] = false;
+            if let Some(connection) = self.pick_connection(cell) {
+                self.maze.set_wall_between(connection, cell, false);
+            }
+            self.add_to_maze(cell);
+        }
+
+        for pos in self.maze.cells.indices() {
+            self.maze.cells[pos].background = CLEAR_COLOR;
+        }
+        for &pos in &self.frontier {
+            self.maze.cells[pos].background = LIGHT_GREY;
+        }
+        self.maze.cells[self.head].background = BLUE;
+    }
+}
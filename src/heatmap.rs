@@ -0,0 +1,119 @@
+use crate::{color::Color, grid::HexGrid, hex::Vector, maze::Maze, solver};
+
+/// The colors that distances are mapped onto, from nearest to farthest.
+const GRADIENT: [[u8; 3]; 5] = [
+    [0x2c, 0x7b, 0xb6],
+    [0xab, 0xd9, 0xe9],
+    [0xff, 0xff, 0xbf],
+    [0xfd, 0xae, 0x61],
+    [0xd7, 0x19, 0x1c],
+];
+
+/// The distance from a start cell to every cell of a maze, for coloring the
+/// maze to show its texture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heatmap {
+    pub distances: HexGrid<Option<u32>>,
+    /// The distance to the farthest reachable cell.
+    pub max: u32,
+}
+
+impl Heatmap {
+    pub fn new(maze: &Maze, start: Vector) -> Self {
+        let distances = solver::distances(maze, start);
+        let max = distances
+            .indices()
+            .filter_map(|pos| distances[pos])
+            .max()
+            .unwrap_or(0);
+        Self { distances, max }
+    }
+
+    /// Sets the background of every cell to its distance's color. Cells that
+    /// cannot be reached show their terrain.
+    pub fn paint(&self, maze: &mut Maze) {
+        for pos in maze.cells.indices() {
+            maze.cells[pos].background = match self.distances.get(pos) {
+                Some(&Some(distance)) => gradient(distance as f64 / self.max.max(1) as f64),
+                _ => maze.terrain[pos].background(),
+            };
+        }
+    }
+}
+
+/// The color for `t` between 0 (nearest) and 1 (farthest), running from blue
+/// through yellow to red.
+pub fn gradient(t: f64) -> Color {
+    let t = t.clamp(0.0, 1.0) * (GRADIENT.len() - 1) as f64;
+    let i = (t as usize).min(GRADIENT.len() - 2);
+    let (from, to, t) = (GRADIENT[i], GRADIENT[i + 1], t - i as f64);
+    let [r, g, b] =
+        [0, 1, 2].map(|c| (from[c] as f64 + (to[c] as f64 - from[c] as f64) * t).round() as u8);
+    Color::Rgb(r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        color::Color,
+        generator::Algorithm,
+        heatmap::{gradient, Heatmap},
+        hex::{Position, Vector},
+        maze::Maze,
+        raster::{self, RasterOptions},
+        terminal::CLEAR_COLOR,
+        terrain::Terrain,
+    };
+
+    #[test]
+    fn test_gradient() {
+        assert_eq!(gradient(0.0), Color::Rgb(0x2c, 0x7b, 0xb6));
+        assert_eq!(gradient(0.5), Color::Rgb(0xff, 0xff, 0xbf));
+        assert_eq!(gradient(1.0), Color::Rgb(0xd7, 0x19, 0x1c));
+        assert_eq!(gradient(2.0), gradient(1.0));
+    }
+
+    #[test]
+    fn test_paint() {
+        let mut maze = Algorithm::Dfs.generator(6, 4, 0).finish().clone();
+
+        let heatmap = Heatmap::new(&maze, Vector::ZERO);
+        // A perfect maze has a route to every cell.
+        assert!(heatmap.max > 0);
+        assert!(heatmap
+            .distances
+            .indices()
+            .all(|pos| heatmap.distances[pos].is_some()));
+
+        heatmap.paint(&mut maze);
+        assert_eq!(maze.cells[Vector::ZERO].background, gradient(0.0));
+        assert!(maze
+            .cells
+            .indices()
+            .all(|pos| maze.cells[pos].background != CLEAR_COLOR));
+
+        // Exported images show the gradient too.
+        let options = RasterOptions::default();
+        let image = raster::render(&maze, None, &options);
+        let center = Vector::ZERO.center(options.cell_radius);
+        let x = (center.0 + options.margin) as u32;
+        let y = (center.1 + options.margin) as u32;
+        assert_eq!(image.pixel(x, y), [0x2c, 0x7b, 0xb6, 0xff]);
+    }
+
+    #[test]
+    fn test_paint_unreachable() {
+        let mut maze = Maze::with_size(2, 2);
+        maze.terrain[Position { col: 1, row: 1 }] = Terrain::Mud;
+        Heatmap::new(&maze, Vector::ZERO).paint(&mut maze);
+        assert_eq!(maze.cells[Vector::ZERO].background, gradient(0.0));
+        assert_eq!(
+            maze.cells[Position { col: 1, row: 1 }].background,
+            Terrain::Mud.background()
+        );
+        assert_eq!(
+            maze.cells[Position { col: 1, row: 0 }].background,
+            Terrain::Plain.background()
+        );
+    }
+}
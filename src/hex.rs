@@ -1,5 +1,8 @@
 use std::ops::{Add, Neg, Sub};
 
+/// The ratio of a hexagon's inradius to its circumradius, `sqrt(3) / 2`.
+pub const HEX_INRADIUS: f64 = 0.866_025_403_784_438_6;
+
 /// A vector in orthogonal coordinates.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Vector {
@@ -69,10 +72,54 @@ impl Vector {
         Self::NORTH_WEST,
     ];
 
+    /// The sum of the sizes of the coordinates. Every step has a length of
+    /// 2, but this is not twice the number of steps: see
+    /// [`Vector::distance`] for that.
     pub const fn length(self) -> i32 {
         self.col.abs() as i32 + self.half_row.abs() as i32
     }
 
+    /// The number of steps it takes to move by this vector across an open
+    /// grid, for vectors between cells.
+    ///
+    /// Each step changes the column by at most one, and the half-row by at
+    /// most two, or by one if the column changes too.
+    pub const fn distance(self) -> i32 {
+        let cols = self.col.unsigned_abs() as i32;
+        let half_rows = self.half_row.unsigned_abs() as i32;
+        if half_rows > cols {
+            cols + (half_rows - cols) / 2
+        } else {
+            cols
+        }
+    }
+
+    /// The center of the hexagon at this position, in a flat-topped layout
+    /// where hexagons have the given circumradius and the grid's top-left
+    /// corner is at the origin. The y axis points down.
+    pub fn center(self, radius: f64) -> (f64, f64) {
+        let x = radius * (1.0 + 1.5 * self.col as f64);
+        let y = radius * HEX_INRADIUS * (1.0 + self.half_row as f64);
+        (x, y)
+    }
+
+    /// The corners of the hexagon at this position, clockwise from the east.
+    ///
+    /// The wall in direction `Vector::DIRECTIONS[i]` runs between corners
+    /// `(i + 4) % 6` and `(i + 5) % 6`.
+    pub fn corners(self, radius: f64) -> [(f64, f64); 6] {
+        let (x, y) = self.center(radius);
+        let dy = radius * HEX_INRADIUS;
+        [
+            (x + radius, y),
+            (x + radius / 2.0, y + dy),
+            (x - radius / 2.0, y + dy),
+            (x - radius, y),
+            (x - radius / 2.0, y - dy),
+            (x + radius / 2.0, y - dy),
+        ]
+    }
+
     pub const fn on_grid(&self) -> bool {
         (self.col + self.half_row) % 2 == 0
     }
@@ -150,6 +197,8 @@ impl From<Position> for Vector {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::{BTreeMap, VecDeque};
+
     use crate::hex::{Position, Vector};
 
     #[test]
@@ -243,4 +292,33 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(Vector::ZERO.distance(), 0);
+        for dir in Vector::DIRECTIONS {
+            assert_eq!(dir.distance(), 1);
+        }
+        assert_eq!(Vector::TWO_EAST.distance(), 2);
+        let v = Vector::NORTH + Vector::NORTH + Vector::NORTH + Vector::SOUTH_WEST;
+        assert_eq!(v.distance(), 3);
+
+        // Count the steps to every cell nearby.
+        let mut steps = BTreeMap::from([(Vector::ZERO, 0)]);
+        let mut queue = VecDeque::from([Vector::ZERO]);
+        while let Some(v) = queue.pop_front() {
+            for dir in Vector::DIRECTIONS {
+                let next = v + dir;
+                if next.length() <= 20 && !steps.contains_key(&next) {
+                    steps.insert(next, steps[&v] + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        for (v, steps) in steps {
+            if v.length() <= 16 {
+                assert_eq!(v.distance(), steps, "{v:?}");
+            }
+        }
+    }
 }
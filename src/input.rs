@@ -0,0 +1,487 @@
+use bitmask_enum::bitmask;
+
+use crate::terminal::ESC;
+
+/// An input event decoded from the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    /// The terminal was resized to the given width and height.
+    Resize(u16, u16),
+}
+
+/// A key press decoded from terminal input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub modifiers: Modifiers,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// A character, including control characters as the letter pressed with
+    /// [`Modifiers::Ctrl`].
+    Char(char),
+    Enter,
+    Tab,
+    Backspace,
+    Esc,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    /// A function key, numbered from 1.
+    F(u8),
+    /// An escape sequence or byte that is not understood.
+    Unknown,
+}
+
+/// A mouse event, reported at a character cell of the screen counted from
+/// zero at the top left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseEvent {
+    pub kind: MouseKind,
+    pub x: u16,
+    pub y: u16,
+    pub modifiers: Modifiers,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseKind {
+    Press(MouseButton),
+    Release(MouseButton),
+    /// The mouse moved with a button held down.
+    Drag(MouseButton),
+    /// The mouse moved with no buttons held down.
+    Move,
+    ScrollUp,
+    ScrollDown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+#[bitmask(u8)]
+pub enum Modifiers {
+    Shift,
+    Alt,
+    Ctrl,
+}
+
+impl KeyEvent {
+    pub const fn new(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: Modifiers::none(),
+        }
+    }
+
+    pub const fn with(key: Key, modifiers: Modifiers) -> Self {
+        Self { key, modifiers }
+    }
+
+    /// Whether this is the character `c` with no modifiers.
+    pub fn is_char(&self, c: char) -> bool {
+        self.key == Key::Char(c) && self.modifiers.is_none()
+    }
+
+    /// Whether this is the character `c` with only Ctrl held.
+    pub fn is_ctrl(&self, c: char) -> bool {
+        self.key == Key::Char(c) && self.modifiers == Modifiers::Ctrl
+    }
+}
+
+impl From<Key> for KeyEvent {
+    fn from(key: Key) -> Self {
+        Self::new(key)
+    }
+}
+
+impl From<Key> for Event {
+    fn from(key: Key) -> Self {
+        Self::Key(key.into())
+    }
+}
+
+impl From<KeyEvent> for Event {
+    fn from(event: KeyEvent) -> Self {
+        Self::Key(event)
+    }
+}
+
+/// Decodes the first event from raw terminal input, returning it along with
+/// the number of bytes it used.
+///
+/// A lone ESC byte may be the Escape key or the start of an escape sequence
+/// that has not fully arrived. Unless `complete` is set, meaning no more
+/// input is coming soon, incomplete input returns `None`.
+///
+/// ```
+/// # use hex_maze::input::{decode, Event, Key};
+/// assert_eq!(decode(b"\x1b[A", false), Some((Event::from(Key::Up), 3)));
+/// assert_eq!(decode(b"\x1b", false), None);
+/// assert_eq!(decode(b"\x1b", true), Some((Event::from(Key::Esc), 1)));
+/// ```
+pub fn decode(bytes: &[u8], complete: bool) -> Option<(Event, usize)> {
+    let &first = bytes.first()?;
+    if first != ESC {
+        return decode_plain(bytes, complete).map(|(key, len)| (key.into(), len));
+    }
+
+    let escaped = match bytes.get(1) {
+        None if complete => return Some((Key::Esc.into(), 1)),
+        None => return None,
+        Some(b'[') if bytes.get(2) == Some(&b'<') => decode_sgr_mouse(bytes),
+        Some(b'[') => decode_csi(bytes),
+        Some(b'O') => decode_ss3(bytes),
+        Some(_) => Decoded::Invalid,
+    };
+    match escaped {
+        Decoded::Event(event, len) => Some((event, len)),
+        Decoded::Incomplete if !complete => None,
+        // Otherwise ESC was pressed together with Alt, or it was pressed on
+        // its own and followed by another key.
+        Decoded::Incomplete | Decoded::Invalid => match decode(&bytes[1..], complete) {
+            Some((Event::Key(KeyEvent { key, modifiers }), len)) if bytes[1] != ESC => Some((
+                KeyEvent::with(key, modifiers | Modifiers::Alt).into(),
+                len + 1,
+            )),
+            _ => Some((Key::Esc.into(), 1)),
+        },
+    }
+}
+
+enum Decoded {
+    Event(Event, usize),
+    Incomplete,
+    Invalid,
+}
+
+fn decode_plain(bytes: &[u8], complete: bool) -> Option<(KeyEvent, usize)> {
+    let first = bytes[0];
+    let key = match first {
+        b'\r' | b'\n' => Key::Enter,
+        b'\t' => Key::Tab,
+        0x08 | 0x7f => Key::Backspace,
+        0x00 => return Some((KeyEvent::with(Key::Char(' '), Modifiers::Ctrl), 1)),
+        0x01..=0x1a => {
+            let c = (first - 1 + b'a') as char;
+            return Some((KeyEvent::with(Key::Char(c), Modifiers::Ctrl), 1));
+        }
+        0x1c..=0x1f => {
+            let c = (first | 0x40) as char;
+            return Some((KeyEvent::with(Key::Char(c), Modifiers::Ctrl), 1));
+        }
+        0x20..=0x7e => Key::Char(first as char),
+        _ => {
+            let len = match first {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => return Some((Key::Unknown.into(), 1)),
+            };
+            if bytes.len() < len {
+                return if complete {
+                    Some((Key::Unknown.into(), 1))
+                } else {
+                    None
+                };
+            }
+            return match std::str::from_utf8(&bytes[..len]) {
+                Ok(s) => Some((Key::Char(s.chars().next().unwrap()).into(), len)),
+                Err(_) => Some((Key::Unknown.into(), 1)),
+            };
+        }
+    };
+    Some((key.into(), 1))
+}
+
+/// Decodes a Control Sequence Introducer sequence, `ESC [ params final`.
+fn decode_csi(bytes: &[u8]) -> Decoded {
+    let Some(end) = bytes[2..]
+        .iter()
+        .position(|b| !(0x20..=0x3f).contains(b))
+        .map(|i| i + 2)
+    else {
+        return Decoded::Incomplete;
+    };
+    let len = end + 1;
+    let final_byte = bytes[end];
+    if !(0x40..=0x7e).contains(&final_byte) {
+        return Decoded::Invalid;
+    }
+
+    let params = std::str::from_utf8(&bytes[2..end])
+        .unwrap_or("")
+        .split(';')
+        .map(|param| param.parse::<u8>().ok())
+        .collect::<Vec<_>>();
+    let param = |i: usize| params.get(i).copied().flatten();
+    let mut modifiers = modifiers_param(param(1));
+
+    let key = match final_byte {
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        b'C' => Key::Right,
+        b'D' => Key::Left,
+        b'H' => Key::Home,
+        b'F' => Key::End,
+        b'P' => Key::F(1),
+        b'Q' => Key::F(2),
+        b'R' => Key::F(3),
+        b'S' => Key::F(4),
+        b'Z' => {
+            modifiers |= Modifiers::Shift;
+            Key::Tab
+        }
+        b'~' => match param(0) {
+            Some(1 | 7) => Key::Home,
+            Some(2) => Key::Insert,
+            Some(3) => Key::Delete,
+            Some(4 | 8) => Key::End,
+            Some(5) => Key::PageUp,
+            Some(6) => Key::PageDown,
+            Some(n @ 11..=15) => Key::F(n - 10),
+            Some(n @ 17..=21) => Key::F(n - 11),
+            Some(n @ 23..=26) => Key::F(n - 12),
+            Some(n @ 28..=29) => Key::F(n - 13),
+            Some(n @ 31..=34) => Key::F(n - 14),
+            _ => Key::Unknown,
+        },
+        _ => Key::Unknown,
+    };
+    Decoded::Event(KeyEvent::with(key, modifiers).into(), len)
+}
+
+/// Decodes a Single Shift 3 sequence, `ESC O final`, which some terminals
+/// send for arrows and F1-F4.
+fn decode_ss3(bytes: &[u8]) -> Decoded {
+    let Some(&final_byte) = bytes.get(2) else {
+        return Decoded::Incomplete;
+    };
+    let key = match final_byte {
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        b'C' => Key::Right,
+        b'D' => Key::Left,
+        b'H' => Key::Home,
+        b'F' => Key::End,
+        b'P' => Key::F(1),
+        b'Q' => Key::F(2),
+        b'R' => Key::F(3),
+        b'S' => Key::F(4),
+        _ => return Decoded::Invalid,
+    };
+    Decoded::Event(key.into(), 3)
+}
+
+/// Decodes an SGR mouse report, `ESC [ < button ; x ; y M` for presses and
+/// motion or `ESC [ < button ; x ; y m` for releases.
+fn decode_sgr_mouse(bytes: &[u8]) -> Decoded {
+    let Some(end) = bytes[3..]
+        .iter()
+        .position(|b| !(b.is_ascii_digit() || *b == b';'))
+        .map(|i| i + 3)
+    else {
+        return Decoded::Incomplete;
+    };
+    let len = end + 1;
+
+    let params = std::str::from_utf8(&bytes[3..end])
+        .unwrap()
+        .split(';')
+        .map(|param| param.parse::<u16>().ok())
+        .collect::<Vec<_>>();
+    let (Some(code), Some(x), Some(y)) = (
+        params.first().copied().flatten(),
+        params.get(1).copied().flatten(),
+        params.get(2).copied().flatten(),
+    ) else {
+        return Decoded::Event(Key::Unknown.into(), len);
+    };
+
+    let mut modifiers = Modifiers::none();
+    if code & 4 != 0 {
+        modifiers |= Modifiers::Shift;
+    }
+    if code & 8 != 0 {
+        modifiers |= Modifiers::Alt;
+    }
+    if code & 16 != 0 {
+        modifiers |= Modifiers::Ctrl;
+    }
+
+    let button = match code & 3 {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None,
+    };
+    let motion = code & 32 != 0;
+    let kind = match (bytes[end], code & 64 != 0, motion, button) {
+        (b'M', true, _, Some(MouseButton::Left)) => MouseKind::ScrollUp,
+        (b'M', true, _, Some(MouseButton::Middle)) => MouseKind::ScrollDown,
+        (b'M', false, true, Some(button)) => MouseKind::Drag(button),
+        (b'M', false, true, None) => MouseKind::Move,
+        (b'M', false, false, Some(button)) => MouseKind::Press(button),
+        (b'm', false, _, Some(button)) => MouseKind::Release(button),
+        (b'M' | b'm', ..) => return Decoded::Event(Key::Unknown.into(), len),
+        _ => return Decoded::Invalid,
+    };
+
+    Decoded::Event(
+        Event::Mouse(MouseEvent {
+            kind,
+            x: x.saturating_sub(1),
+            y: y.saturating_sub(1),
+            modifiers,
+        }),
+        len,
+    )
+}
+
+/// Decodes the xterm modifier parameter, which is one more than a bitmask of
+/// Shift, Alt and Ctrl.
+fn modifiers_param(param: Option<u8>) -> Modifiers {
+    let bits = param.unwrap_or(1).saturating_sub(1);
+    Modifiers::from(bits).truncate()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::{
+        decode, Event, Key, KeyEvent, Modifiers, MouseButton, MouseEvent, MouseKind,
+    };
+
+    fn decode_all(mut bytes: &[u8]) -> Vec<Event> {
+        let mut events = Vec::new();
+        while let Some((event, len)) = decode(bytes, true) {
+            events.push(event);
+            bytes = &bytes[len..];
+        }
+        events
+    }
+
+    fn keys(keys: impl IntoIterator<Item = KeyEvent>) -> Vec<Event> {
+        keys.into_iter().map(Event::Key).collect()
+    }
+
+    fn mouse(kind: MouseKind, x: u16, y: u16) -> Event {
+        Event::Mouse(MouseEvent {
+            kind,
+            x,
+            y,
+            modifiers: Modifiers::none(),
+        })
+    }
+
+    #[test]
+    fn test_decode_plain() {
+        assert_eq!(
+            decode_all(b"a\x03\r\t\x7f"),
+            keys([
+                KeyEvent::new(Key::Char('a')),
+                KeyEvent::with(Key::Char('c'), Modifiers::Ctrl),
+                KeyEvent::new(Key::Enter),
+                KeyEvent::new(Key::Tab),
+                KeyEvent::new(Key::Backspace),
+            ])
+        );
+        assert_eq!(decode_all("é".as_bytes()), [Event::from(Key::Char('é'))]);
+        assert_eq!(decode(&"é".as_bytes()[..1], false), None);
+    }
+
+    #[test]
+    fn test_decode_sequences() {
+        assert_eq!(
+            decode_all(
+                b"\x1b[A\x1b[B\x1bOC\x1b[D\x1b[H\x1b[4~\x1b[5~\x1b[6~\x1b[3~\x1bOP\x1b[15~\x1b[24~"
+            ),
+            keys(
+                [
+                    Key::Up,
+                    Key::Down,
+                    Key::Right,
+                    Key::Left,
+                    Key::Home,
+                    Key::End,
+                    Key::PageUp,
+                    Key::PageDown,
+                    Key::Delete,
+                    Key::F(1),
+                    Key::F(5),
+                    Key::F(12),
+                ]
+                .map(KeyEvent::new)
+            )
+        );
+    }
+
+    #[test]
+    fn test_decode_modifiers() {
+        assert_eq!(
+            decode_all(b"\x1b[1;5A\x1b[1;2D\x1b[3;7~\x1b[Z\x1bq"),
+            keys([
+                KeyEvent::with(Key::Up, Modifiers::Ctrl),
+                KeyEvent::with(Key::Left, Modifiers::Shift),
+                KeyEvent::with(Key::Delete, Modifiers::Ctrl | Modifiers::Alt),
+                KeyEvent::with(Key::Tab, Modifiers::Shift),
+                KeyEvent::with(Key::Char('q'), Modifiers::Alt),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_decode_escape() {
+        assert_eq!(decode(b"\x1b", false), None);
+        assert_eq!(decode(b"\x1b[1;", false), None);
+        assert_eq!(decode_all(b"\x1b"), [Event::from(Key::Esc)]);
+        assert_eq!(
+            decode_all(b"\x1b\x1b"),
+            [Event::from(Key::Esc), Event::from(Key::Esc)]
+        );
+        assert_eq!(
+            decode_all(b"\x1b["),
+            keys([KeyEvent::with(Key::Char('['), Modifiers::Alt)])
+        );
+        assert_eq!(decode_all(b"\x1b[99x"), [Event::from(Key::Unknown)]);
+    }
+
+    #[test]
+    fn test_decode_mouse() {
+        assert_eq!(
+            decode_all(
+                b"\x1b[<0;5;3M\x1b[<32;6;3M\x1b[<0;6;3m\x1b[<2;1;1M\x1b[<65;10;10M\x1b[<35;2;2M"
+            ),
+            [
+                mouse(MouseKind::Press(MouseButton::Left), 4, 2),
+                mouse(MouseKind::Drag(MouseButton::Left), 5, 2),
+                mouse(MouseKind::Release(MouseButton::Left), 5, 2),
+                mouse(MouseKind::Press(MouseButton::Right), 0, 0),
+                mouse(MouseKind::ScrollDown, 9, 9),
+                mouse(MouseKind::Move, 1, 1),
+            ]
+        );
+        assert_eq!(
+            decode_all(b"\x1b[<16;1;1M"),
+            [Event::Mouse(MouseEvent {
+                kind: MouseKind::Press(MouseButton::Left),
+                x: 0,
+                y: 0,
+                modifiers: Modifiers::Ctrl,
+            })]
+        );
+        assert_eq!(decode(b"\x1b[<0;12", false), None);
+    }
+}
@@ -1,15 +1,18 @@
+pub mod color;
+pub mod endpoint;
+pub mod game;
 pub mod generator;
 pub mod grid;
+pub mod heatmap;
 pub mod hex;
+pub mod input;
 pub mod maze;
+pub mod raster;
+pub mod recorder;
 pub mod solver;
+pub mod style;
+pub mod svg;
 pub mod terminal;
+pub mod terrain;
 
 mod sys;
-
-const _SAMPLE_MAZE: &str = r#"
- ___     ___
-/   \___/   \
-\___/   \___/
-    \___/
-"#;
@@ -1,96 +1,565 @@
-use std::{time::Duration, vec::IntoIter};
+use std::{env, mem, process, time::Duration, vec::IntoIter};
 
+use cli::{Command, Options};
 use hex_maze::{
-    generator::Generator,
-    hex::Vector,
+    endpoint::{self, Endpoint},
+    game::Game,
+    generator::{BraidGenerator, MazeGenerator, Stream},
+    heatmap::Heatmap,
+    hex::{Position, Vector},
+    input::{Event, Key, KeyEvent, MouseButton, MouseEvent, MouseKind},
+    maze::analysis::Analysis,
     maze::Maze,
-    solver::Solver,
-    terminal::{Terminal, CLEAR_COLOR, CTRL_C, ESC, GREEN},
+    solver::{self, MazeSolver, RouteSolver},
+    terminal::{Canvas, Screen, Terminal, CLEAR_COLOR, GREEN, MAGENTA},
+    terrain,
 };
 
+mod cli;
+
 fn main() {
+    let mut options = match cli::parse(env::args().skip(1)) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::Help) => {
+            print!("{}", cli::USAGE);
+            return;
+        }
+        Err(err) => fail(&err),
+    };
+
+    // Set the seed here so that random endpoints stay put for each maze.
+    let mut seed = *options.seed.get_or_insert_with(rand::random);
+    if options.stats {
+        if let Err(err) = options.check_cells(Some(options.size.unwrap_or(STATS_SIZE))) {
+            fail(&err);
+        }
+        print_stats(&options);
+        return;
+    }
+
     let mut term = Terminal::new();
+    let (width, height) = term.size();
+    let size = options
+        .size
+        .unwrap_or(options.style.fit_screen(width, height));
+    if let Err(err) = options.check_cells(Some(size)) {
+        drop(term);
+        fail(&err);
+    }
+    term.mouse_reporting(true).flush();
+    let mut screen = Screen::new(width, height);
+    screen.color_support = term.color_support;
 
-    let mut state = State::new(&term);
+    let mut state = State::new(&term, &options, seed);
+    let mut redraw = true;
     'main_loop: while !term.should_exit() {
-        state.step();
+        let was_done = state.is_done();
+        if options.animate {
+            state.step(&options);
+        } else {
+            while state.is_animating() {
+                state.step(&options);
+            }
+        }
 
-        if !state.is_done() {
-            state.maze().draw(&mut term);
-            term.flush();
+        if redraw || !was_done {
+            screen.clear();
+            options.style.draw(state.maze(), &mut screen);
+            let mut status = state.status(&options, seed);
+            if options.size.is_none() && !state.fits(&term, &options) {
+                // First, so that a narrower terminal doesn't clip it.
+                status = format!("r: regenerate to fit  {status}");
+            }
+            draw_status(&mut screen, &status);
+            term.present(&mut screen).flush();
+            redraw = false;
         }
 
-        let mut timeout = Duration::from_millis(16);
-        while term.poll(timeout) > 0 {
-            match term.read() {
-                CTRL_C | ESC | b'q' => break 'main_loop,
-                b'r' => {
-                    term.clear();
-                    state = State::new(&term);
+        let mut timeout = options.delay;
+        while let Some(event) = term.read_event(timeout) {
+            timeout = Duration::ZERO;
+
+            let event = match event {
+                Event::Key(event) => event,
+                Event::Mouse(event) => {
+                    state.click(event, &mut options);
+                    redraw = true;
+                    continue;
+                }
+                Event::Resize(width, height) => {
+                    screen.resize(width, height);
+                    redraw = true;
+                    continue;
+                }
+            };
+
+            if let State::Playing(game) = &mut state {
+                if let Some(dir) = direction_key(event) {
+                    game.try_move(dir);
+                    continue;
+                }
+                if event.is_char('s') {
+                    game.toggle_solution();
+                    continue;
                 }
-                _ => {}
             }
 
-            timeout = Duration::ZERO;
+            if event.is_ctrl('c') || event.key == Key::Esc || event.is_char('q') {
+                break 'main_loop;
+            } else if event.is_char('r') {
+                seed = rand::random();
+                options.seed = Some(seed);
+                state = State::new(&term, &options, seed);
+                redraw = true;
+            } else if event.is_char('g') {
+                options.generator = options.generator.next();
+                state = State::new(&term, &options, seed);
+                redraw = true;
+            } else if event.is_char('a') {
+                options.solver = options.solver.next();
+                if let Some(maze) = state.finished_maze() {
+                    state = State::solve(maze, &options);
+                }
+            } else if event.is_char('p') {
+                if let Some(maze) = state.finished_maze() {
+                    state = State::play(maze, &options);
+                }
+            } else if event.is_char('v') {
+                options.style = options.style.next();
+                redraw = true;
+            } else if event.is_char('h') {
+                if let Some(maze) = state.finished_maze() {
+                    state = if matches!(state, State::Heatmap(..)) {
+                        State::Done(maze)
+                    } else {
+                        State::heatmap(maze, &options)
+                    };
+                    redraw = true;
+                }
+            } else if event.is_char('e') {
+                if let State::Editing(maze) = &state {
+                    state = State::solve(maze.clone(), &options);
+                } else if let Some(maze) = state.finished_maze() {
+                    state = State::Editing(maze);
+                }
+            }
+        }
+    }
+}
+
+/// The size of maze that `--stats` analyses by default.
+const STATS_SIZE: (u16, u16) = (20, 10);
+
+/// Reports an error in the command-line arguments and exits.
+fn fail(err: &str) -> ! {
+    eprintln!("error: {err}\n\nFor more information, try '--help'.");
+    process::exit(2);
+}
+
+/// Prints an analysis of the maze instead of showing it.
+fn print_stats(options: &Options) {
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let (cols, rows) = options.size.unwrap_or(STATS_SIZE);
+    let mut maze = generator(options, cols, rows, seed).finish().clone();
+    lay_terrain(&mut maze, options);
+    let (start, goals) = endpoints(&mut maze, options);
+    let goal = nearest(&maze, start, &goals);
+    let maze = &maze;
+    println!("generator:       {}", options.generator);
+    println!("seed:            {seed}");
+    if options.braid > 0.0 {
+        println!("braid:           {}", options.braid);
+    }
+    print!("{}", Analysis::with_endpoints(maze, start, goal));
+
+    // Compare how much of the maze each solver explores.
+    println!();
+    let mut solvers: Vec<_> = solver::Algorithm::ALL
+        .into_iter()
+        .map(|algorithm| {
+            (
+                algorithm.name(),
+                algorithm.solver(maze.clone(), start, goal),
+            )
+        })
+        .collect();
+    if is_route(options) {
+        let via = waypoints(maze, options, start, &goals);
+        let solver = RouteSolver::new(maze.clone(), start, via, goals);
+        solvers.push(("route", Box::new(solver)));
+    }
+    for (name, mut solver) in solvers {
+        let mut steps = 0;
+        while !solver.is_done() {
+            solver.step();
+            steps += 1;
         }
+        let visited = solver.visited();
+        let explored = visited.indices().filter(|&pos| visited[pos]).count();
+        let route = match solver.path().len() {
+            0 => "no route".to_owned(),
+            cells if options.terrain => {
+                let cost = solver::cost(maze, solver.path());
+                format!("{} moves costing {cost}", cells - 1)
+            }
+            cells => format!("{} moves", cells - 1),
+        };
+        println!(
+            "{:<17}{route}, explored {explored} cells in {steps} steps",
+            format!("{name}:")
+        );
+    }
+}
+
+/// Creates the generator from the options, growing the maze from the start if
+/// it is a particular cell, and braiding it once it is generated if asked
+/// to.
+fn generator(options: &Options, cols: u16, rows: u16, seed: u64) -> Box<dyn MazeGenerator> {
+    let start = options
+        .start
+        .and_then(|start| start.cell(cols, rows))
+        .unwrap_or(Vector::ZERO);
+    let generator = options.generator.generator_from(cols, rows, seed, start);
+    if options.braid > 0.0 {
+        Box::new(BraidGenerator::with_seed(generator, options.braid, seed))
+    } else {
+        generator
+    }
+}
+
+/// Scatters terrain over the finished maze if asked to, using the seed so
+/// that it stays put for each maze.
+fn lay_terrain(maze: &mut Maze, options: &Options) {
+    if options.terrain {
+        let mut rng = Stream::Terrain.rng(options.seed.unwrap_or_default());
+        terrain::scatter(maze, &mut rng);
+        maze.clear_backgrounds();
     }
 }
 
+/// The seed, along with the start cell if there is one, since the maze is
+/// grown from it. Together they are what `--seed` and `--start` need to
+/// generate the same maze again.
+fn seed_status(options: &Options, seed: u64) -> String {
+    match options.start {
+        Some(Endpoint::Cell(Position { col, row })) => format!("seed: {seed}  start: {col},{row}"),
+        _ => format!("seed: {seed}"),
+    }
+}
+
+fn draw_status(screen: &mut Screen, status: &str) {
+    let (_, height) = screen.size();
+    screen.write_str(
+        0,
+        height.saturating_sub(1),
+        status,
+        CLEAR_COLOR,
+        CLEAR_COLOR,
+    );
+}
+
+/// Maps keys to directions, laid out like the hexagon's sides on the numeric
+/// keypad (`7 8 9` / `1 2 3`, or `Home Up PgUp` / `End Down PgDn` with Num
+/// Lock off) and on the letters `u i o` / `j k l`.
+fn direction_key(event: KeyEvent) -> Option<Vector> {
+    if !event.modifiers.is_none() {
+        return None;
+    }
+    match event.key {
+        Key::Char('8' | 'i') | Key::Up => Some(Vector::NORTH),
+        Key::Char('9' | 'o') | Key::PageUp => Some(Vector::NORTH_EAST),
+        Key::Char('3' | 'l') | Key::PageDown => Some(Vector::SOUTH_EAST),
+        Key::Char('2' | 'k') | Key::Down => Some(Vector::SOUTH),
+        Key::Char('1' | 'j') | Key::End => Some(Vector::SOUTH_WEST),
+        Key::Char('7' | 'u') | Key::Home => Some(Vector::NORTH_WEST),
+        _ => None,
+    }
+}
+
+/// The start and goal cells from the options, defaulting to opposite corners.
+/// Random cells are picked using the seed, so they stay put for each maze.
+///
+/// Also cuts openings in the outer wall next to them, if asked to, removing
+/// any cut for earlier endpoints.
+fn endpoints(maze: &mut Maze, options: &Options) -> (Vector, Vec<Vector>) {
+    let start = options.start.unwrap_or(Endpoint::TOP_LEFT);
+    let goals = match options.goals.as_slice() {
+        [] => &[Endpoint::BOTTOM_RIGHT],
+        goals => goals,
+    };
+    let mut rng = Stream::Endpoints.rng(options.seed.unwrap_or_default());
+    let (start, goal) = endpoint::place(maze, start, goals[0], &mut rng);
+    let mut placed = vec![goal];
+    for &goal in &goals[1..] {
+        let start = Endpoint::Cell(start.into());
+        placed.push(endpoint::place(maze, start, goal, &mut rng).1);
+    }
+
+    maze.openings.clear();
+    if options.openings {
+        for &pos in [start].iter().chain(&placed) {
+            maze.cut_opening(pos);
+        }
+    }
+    (start, placed)
+}
+
+/// The goal with the cheapest route from `start`, as the solver would pick.
+fn nearest(maze: &Maze, start: Vector, goals: &[Vector]) -> Vector {
+    let costs = solver::costs(maze, start);
+    *goals
+        .iter()
+        .min_by_key(|&&goal| costs[goal].unwrap_or(u32::MAX))
+        .unwrap()
+}
+
+/// The cells to pass through from the options, in the order given.
+fn via(maze: &Maze, options: &Options) -> Vec<Vector> {
+    let (cols, rows) = (maze.cells.cols(), maze.cells.rows());
+    options
+        .via
+        .iter()
+        .filter_map(|&pos| Endpoint::Cell(pos).cell(cols, rows))
+        .collect()
+}
+
+/// The cells to pass through, in the best order if asked for.
+fn waypoints(maze: &Maze, options: &Options, start: Vector, goals: &[Vector]) -> Vec<Vector> {
+    let via = via(maze, options);
+    if options.best_order {
+        solver::best_order(maze, start, &via, goals)
+    } else {
+        via
+    }
+}
+
+/// Whether the options call for a route through waypoints or to one of
+/// several goals, which is always found with A*.
+fn is_route(options: &Options) -> bool {
+    !options.via.is_empty() || options.goals.len() > 1
+}
+
 enum State {
-    Generating(Generator),
-    Solving(Solver),
-    Backfilling(Maze, IntoIter<Vector>),
+    Generating(Box<dyn MazeGenerator>),
+    /// The solver, and the waypoints it passes through.
+    Solving(Box<dyn MazeSolver>, Vec<Vector>),
+    /// The maze, the rest of the path to color in, and the waypoints on it.
+    Backfilling(Maze, IntoIter<Vector>, Vec<Vector>),
+    Playing(Game),
+    Editing(Maze),
+    /// The maze colored by distance from the start, and the farthest
+    /// distance.
+    Heatmap(Maze, u32),
     Done(Maze),
 }
 
 impl State {
-    pub fn new(term: &Terminal) -> Self {
-        Self::Generating(Generator::new(term))
+    pub fn new(term: &Terminal, options: &Options, seed: u64) -> Self {
+        let (cols, rows) = options.size.unwrap_or_else(|| {
+            let (width, height) = term.size();
+            options.style.fit_screen(width, height)
+        });
+        Self::Generating(generator(options, cols, rows, seed))
     }
 
-    pub fn step(&mut self) {
+    pub fn step(&mut self, options: &Options) {
         match self {
             Self::Generating(generator) => {
                 generator.step();
 
-                if generator.is_done {
-                    let maze = generator.maze.clone();
-                    *self = Self::Solving(Solver::new(maze));
+                if generator.is_done() {
+                    let mut maze = generator.maze().clone();
+                    lay_terrain(&mut maze, options);
+                    if maze.cells.is_empty() {
+                        *self = Self::Done(maze);
+                        return;
+                    }
+
+                    *self = if options.play {
+                        Self::play(maze, options)
+                    } else {
+                        Self::solve(maze, options)
+                    };
                 }
             }
-            Self::Solving(solver) => {
+            Self::Solving(solver, via) => {
                 solver.step();
 
-                if solver.is_done {
-                    let mut maze = solver.maze.clone();
-                    for pos in maze.cells.indices() {
-                        maze.cells[pos].background = CLEAR_COLOR;
-                    }
-                    let path = solver.path.clone();
-                    *self = Self::Backfilling(maze, path.into_iter());
+                if solver.is_done() {
+                    let mut maze = solver.maze().clone();
+                    maze.clear_backgrounds();
+                    let path = solver.path().to_vec();
+                    *self = Self::Backfilling(maze, path.into_iter(), mem::take(via));
                 }
             }
-            Self::Backfilling(maze, path) => {
+            Self::Backfilling(maze, path, via) => {
                 if let Some(pos) = path.next() {
-                    maze.cells[pos].background = GREEN;
+                    // Waypoints stand out along the route.
+                    maze.cells[pos].background = if via.contains(&pos) { MAGENTA } else { GREEN };
                 } else {
                     *self = Self::Done(maze.clone());
                 }
             }
-            Self::Done(_) => {}
+            Self::Playing(_) | Self::Editing(_) | Self::Heatmap(..) | Self::Done(_) => {}
+        }
+    }
+
+    /// Solves `maze` between the endpoints from the options, through any
+    /// waypoints.
+    pub fn solve(mut maze: Maze, options: &Options) -> Self {
+        let (start, goals) = endpoints(&mut maze, options);
+        if !is_route(options) {
+            return Self::Solving(options.solver.solver(maze, start, goals[0]), Vec::new());
         }
+        let via = waypoints(&maze, options, start, &goals);
+        let solver = RouteSolver::new(maze, start, via.clone(), goals);
+        Self::Solving(Box::new(solver), via)
     }
 
+    /// Lets the player walk `maze` to the nearest goal from the options.
+    pub fn play(mut maze: Maze, options: &Options) -> Self {
+        let (start, goals) = endpoints(&mut maze, options);
+        let goal = nearest(&maze, start, &goals);
+        Self::Playing(Game::new(maze, start, goal))
+    }
+
+    /// Colors `maze` by distance from the start in the options.
+    pub fn heatmap(mut maze: Maze, options: &Options) -> Self {
+        let (start, _) = endpoints(&mut maze, options);
+        let heatmap = Heatmap::new(&maze, start);
+        heatmap.paint(&mut maze);
+        Self::Heatmap(maze, heatmap.max)
+    }
+
+    /// The finished maze with backgrounds cleared, once generation is done.
+    pub fn finished_maze(&self) -> Option<Maze> {
+        let mut maze = match self {
+            Self::Playing(game) => game.maze().clone(),
+            Self::Editing(maze) | Self::Heatmap(maze, _) | Self::Done(maze) => maze.clone(),
+            _ => return None,
+        };
+        if maze.cells.is_empty() {
+            return None;
+        }
+
+        maze.clear_backgrounds();
+        Some(maze)
+    }
+
+    /// Handles the mouse: clicking moves the player while playing, toggles
+    /// walls while editing, and on a solved maze chooses a new start (left
+    /// button) or goal (right button).
+    pub fn click(&mut self, event: MouseEvent, options: &mut Options) {
+        let (x, y) = (event.x, event.y);
+        match (&mut *self, event.kind) {
+            (Self::Playing(game), MouseKind::Press(MouseButton::Left)) => {
+                if let Some(pos) = options.style.position_at(game.maze(), x, y) {
+                    game.try_move(Vector::from(pos) - game.player());
+                }
+            }
+            (
+                Self::Editing(maze),
+                MouseKind::Press(MouseButton::Left) | MouseKind::Drag(MouseButton::Left),
+            ) => {
+                if let Some((a, b)) = options.style.wall_at(maze, x, y) {
+                    let wall = maze.wall_between(a, b);
+                    maze.set_wall_between(a, b, !wall);
+                }
+            }
+            (Self::Heatmap(maze, _), MouseKind::Press(MouseButton::Left)) => {
+                let Some(pos) = options.style.position_at(maze, x, y) else {
+                    return;
+                };
+                options.start = Some(Endpoint::Cell(pos));
+                if let Some(maze) = self.finished_maze() {
+                    *self = Self::heatmap(maze, options);
+                }
+            }
+            (Self::Done(maze), MouseKind::Press(button)) => {
+                let Some(pos) = options.style.position_at(maze, x, y) else {
+                    return;
+                };
+                match button {
+                    MouseButton::Left => options.start = Some(Endpoint::Cell(pos)),
+                    MouseButton::Right => options.goals = vec![Endpoint::Cell(pos)],
+                    MouseButton::Middle => return,
+                }
+                if let Some(maze) = self.finished_maze() {
+                    *self = Self::solve(maze, options);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the picture is final and needs no more redrawing.
     pub fn is_done(&self) -> bool {
-        matches!(self, Self::Done(_))
+        matches!(self, Self::Heatmap(..) | Self::Done(_))
+    }
+
+    /// Whether an algorithm is still running.
+    pub fn is_animating(&self) -> bool {
+        !matches!(
+            self,
+            Self::Playing(_) | Self::Editing(_) | Self::Heatmap(..) | Self::Done(_)
+        )
+    }
+
+    pub fn status(&self, options: &Options, seed: u64) -> String {
+        match self {
+            Self::Playing(game) => {
+                let seconds = game.elapsed().as_secs_f32();
+                let optimal = game.optimal_moves();
+                if game.is_won() {
+                    format!(
+                        "solved in {} moves, {seconds:.1}s (optimal {optimal})  p: play again",
+                        game.moves()
+                    )
+                } else {
+                    format!(
+                        "moves: {}  time: {seconds:.1}s  s: show solution",
+                        game.moves()
+                    )
+                }
+            }
+            Self::Editing(_) => "editing: click walls to toggle them  e: solve".to_owned(),
+            Self::Heatmap(_, max) => {
+                format!("heatmap: farthest cell is {max} moves away  click: set start  h: hide")
+            }
+            Self::Done(_) => format!(
+                "generator: {}  {}  p: play  e: edit  h: heatmap",
+                options.generator,
+                seed_status(options, seed)
+            ),
+            Self::Solving(..) | Self::Backfilling(..) if is_route(options) => {
+                format!("solver: astar route  seed: {seed}")
+            }
+            Self::Solving(..) | Self::Backfilling(..) => {
+                format!("solver: {}  seed: {seed}", options.solver)
+            }
+            Self::Generating(_) => format!(
+                "generator: {}  {}",
+                options.generator,
+                seed_status(options, seed)
+            ),
+        }
+    }
+
+    /// Whether the maze is the size that would be generated to fit the
+    /// terminal now.
+    pub fn fits(&self, term: &Terminal, options: &Options) -> bool {
+        let (width, height) = term.size();
+        let cells = &self.maze().cells;
+        options.style.fit_screen(width, height) == (cells.cols(), cells.rows())
     }
 
     pub fn maze(&self) -> &Maze {
         match self {
-            Self::Generating(generator) => &generator.maze,
-            Self::Solving(solver) => &solver.maze,
-            Self::Backfilling(maze, _) => maze,
-            Self::Done(maze) => maze,
+            Self::Generating(generator) => generator.maze(),
+            Self::Solving(solver, _) => solver.maze(),
+            Self::Backfilling(maze, ..) => maze,
+            Self::Playing(game) => game.maze(),
+            Self::Editing(maze) | Self::Heatmap(maze, _) | Self::Done(maze) => maze,
         }
     }
 }
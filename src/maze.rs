@@ -1,12 +1,28 @@
+use std::{error::Error, fmt, str::FromStr};
+
 use crate::{
+    color::Color,
     grid::HexGrid,
-    hex::Vector,
-    terminal::{Terminal, CLEAR_COLOR},
+    hex::{Position, Vector, HEX_INRADIUS},
+    terminal::{Canvas, CLEAR_COLOR},
+    terrain::Terrain,
 };
 
+pub mod analysis;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod validation;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Maze {
     pub cells: HexGrid<Cell>,
+    /// Gaps in the outer boundary marking the entrance and exit, each given
+    /// as a cell and the index into [`Vector::DIRECTIONS`] of its side. They
+    /// are only drawn: [`Maze::wall_between`] still treats the boundary as
+    /// walled, so nothing can leave the maze through them.
+    pub openings: Vec<(Vector, usize)>,
+    /// The ground in each cell, which costs more or less to cross.
+    pub terrain: HexGrid<Terrain>,
 }
 
 /// Each cell keeps track of its north-east, south, and north-west walls.
@@ -16,7 +32,7 @@ pub struct Cell {
     pub north_east: bool,
     pub south: bool,
     pub north_west: bool,
-    pub background: u8,
+    pub background: Color,
 }
 
 impl Default for Cell {
@@ -31,27 +47,60 @@ impl Default for Cell {
 }
 
 impl Maze {
-    pub fn new(term: &Terminal) -> Self {
-        let (term_width, term_height) = term.size();
-        if term_width < 11 || term_height < 7 {
-            return Self::empty();
-        }
-
-        let cols = (term_width - 1) / 4;
-        let rows = (term_height - 2) / 2;
+    pub fn with_size(cols: u16, rows: u16) -> Self {
         Self {
             cells: HexGrid::new_with(cols, rows, |_| Cell::default()),
+            openings: Vec::new(),
+            terrain: HexGrid::new_with(cols, rows, |_| Terrain::default()),
+        }
+    }
+
+    /// The largest maze dimensions, in cells, that can be drawn on a screen
+    /// of the given size. Leaves the bottom line free for the status bar.
+    pub fn fit_screen(width: u16, height: u16) -> (u16, u16) {
+        if width < 11 || height < 7 {
+            return (0, 0);
         }
+
+        let cols = (width - 1) / 4;
+        let rows = (height - 3) / 2;
+        (cols, rows)
     }
 
     pub fn empty() -> Maze {
         Self {
             cells: HexGrid::empty(),
+            openings: Vec::new(),
+            terrain: HexGrid::empty(),
         }
     }
 
     pub fn copy_from(&mut self, other: &Maze) {
         self.cells.copy_from(&other.cells);
+        self.openings.clone_from(&other.openings);
+        self.terrain.copy_from(&other.terrain);
+    }
+
+    /// Resets every cell's background to show its terrain.
+    pub fn clear_backgrounds(&mut self) {
+        for pos in self.cells.indices() {
+            self.cells[pos].background = self.terrain[pos].background();
+        }
+    }
+
+    /// The size of the maze when drawn with hexagons of the given
+    /// circumradius, as laid out by [`Vector::center`].
+    pub fn extent(&self, radius: f64) -> (f64, f64) {
+        if self.cells.is_empty() {
+            return (0.0, 0.0);
+        }
+
+        let cols = self.cells.cols() as f64;
+        let half_rows = 2.0 * self.cells.rows() as f64 + if cols > 1.0 { 1.0 } else { 0.0 };
+        (
+            radius * (0.5 + 1.5 * cols),
+            radius * HEX_INRADIUS * half_rows,
+        )
     }
 
     pub fn wall_between(&self, a: Vector, b: Vector) -> bool {
@@ -87,70 +136,626 @@ impl Maze {
         }
     }
 
-    fn horizontal_wall_at(&self, coords: Vector) -> bool {
+    /// Whether the boundary wall between `a` and `b` has an opening cut in it.
+    pub fn is_opening(&self, a: Vector, b: Vector) -> bool {
+        self.openings.iter().any(|&(pos, side)| {
+            let outside = pos + Vector::DIRECTIONS[side];
+            (pos, outside) == (a, b) || (outside, pos) == (a, b)
+        })
+    }
+
+    /// Whether a wall is drawn between `a` and `b`, which is where there is a
+    /// wall and no opening.
+    pub fn is_wall_drawn(&self, a: Vector, b: Vector) -> bool {
+        self.wall_between(a, b) && !self.is_opening(a, b)
+    }
+
+    /// Cuts an opening in the boundary wall of a cell on the edge of the
+    /// maze, on the side facing most directly away from the middle of the
+    /// maze. Returns the side, or `None` if the cell isn't on the edge.
+    pub fn cut_opening(&mut self, pos: Vector) -> Option<usize> {
+        if !self.cells.contains(pos) {
+            return None;
+        }
+
+        let (width, height) = self.extent(1.0);
+        let (x, y) = pos.center(1.0);
+        let (dx, dy) = (x - width / 2.0, y - height / 2.0);
+        let (origin_x, origin_y) = Vector::ZERO.center(1.0);
+        let outward = |side: usize| {
+            let (x, y) = Vector::DIRECTIONS[side].center(1.0);
+            (x - origin_x) * dx + (y - origin_y) * dy
+        };
+        let side = (0..6)
+            .filter(|&side| !self.cells.contains(pos + Vector::DIRECTIONS[side]))
+            .max_by(|&a, &b| outward(a).total_cmp(&outward(b)))?;
+        if !self.openings.contains(&(pos, side)) {
+            self.openings.push((pos, side));
+        }
+        Some(side)
+    }
+
+    /// The cells next to `pos` with no wall in between.
+    pub fn open_neighbors(&self, pos: Vector) -> impl Iterator<Item = Vector> + '_ {
+        Vector::DIRECTIONS
+            .into_iter()
+            .map(move |dir| pos + dir)
+            .filter(move |&neighbor| !self.wall_between(pos, neighbor))
+    }
+
+    /// How many neighboring cells can be reached from `pos`. Openings don't
+    /// count, since they are walls as far as moving is concerned.
+    pub fn degree(&self, pos: Vector) -> usize {
+        self.open_neighbors(pos).count()
+    }
+
+    /// Every wall in the maze, including the outer boundary but not its
+    /// openings. Each wall is given once, as a cell and the index into [`Vector::DIRECTIONS`] of the
+    /// side of that cell it lies on.
+    pub fn walls(&self) -> impl Iterator<Item = (Vector, usize)> + '_ {
+        self.cells.indices().map(Vector::from).flat_map(move |pos| {
+            Vector::DIRECTIONS
+                .into_iter()
+                .enumerate()
+                .filter(move |&(i, dir)| {
+                    let neighbor = pos + dir;
+                    // Walls between two cells are owned by the cell holding
+                    // them, i.e. its north-east, south and north-west sides.
+                    let owned = !self.cells.contains(neighbor) || i % 2 == 1;
+                    owned && self.is_wall_drawn(pos, neighbor)
+                })
+                .map(move |(i, _)| (pos, i))
+        })
+    }
+
+    /// The cells above and below a horizontal wall drawn at `coords`.
+    fn horizontal_wall_cells(coords: Vector) -> (Vector, Vector) {
         let coords = coords.nearest_north();
         let above = coords;
         let below = coords + Vector::SOUTH;
-        self.wall_between(above, below)
+        (above, below)
     }
 
-    fn vertical_wall_at(&self, coords: Vector) -> bool {
-        let (left, right) = if coords.on_grid() {
+    /// The cells to the left and right of a slanted wall drawn at `coords`.
+    fn vertical_wall_cells(coords: Vector) -> (Vector, Vector) {
+        if coords.on_grid() {
             (coords + Vector::NORTH_WEST, coords)
         } else {
             let coords = coords.nearest_north();
             (coords + Vector::SOUTH_WEST, coords)
+        }
+    }
+
+    fn horizontal_wall_at(&self, coords: Vector) -> bool {
+        let (above, below) = Self::horizontal_wall_cells(coords);
+        self.is_wall_drawn(above, below)
+    }
+
+    fn vertical_wall_at(&self, coords: Vector) -> bool {
+        let (left, right) = Self::vertical_wall_cells(coords);
+        self.is_wall_drawn(left, right)
+    }
+
+    /// The cell drawn at a character of the screen by [`Maze::draw`], if any.
+    /// Characters where slanted walls may be drawn belong to no cell.
+    pub fn position_at(&self, x: u16, y: u16) -> Option<Position> {
+        if x.is_multiple_of(4) {
+            return None;
+        }
+
+        let coords = Vector {
+            col: (x / 4) as i16,
+            half_row: y as i16 - 1,
+        }
+        .nearest_north();
+        self.cells.contains(coords).then(|| coords.into())
+    }
+
+    /// The two cells either side of a wall drawn at a character of the
+    /// screen by [`Maze::draw`], if the character is where a wall between
+    /// two cells of the maze may be drawn.
+    pub fn wall_at(&self, x: u16, y: u16) -> Option<(Vector, Vector)> {
+        let coords = Vector {
+            col: (x / 4) as i16,
+            half_row: y as i16 - 1,
+        };
+        let (a, b) = if x.is_multiple_of(4) {
+            Self::vertical_wall_cells(coords)
+        } else if !coords.on_grid() {
+            Self::horizontal_wall_cells(coords)
+        } else {
+            return None;
         };
-        self.wall_between(left, right)
+        (self.cells.contains(a) && self.cells.contains(b)).then_some((a, b))
     }
 
-    pub fn draw(&self, term: &mut Terminal) {
-        term.sgr().reset();
+    fn vertical_glyph(&self, coords: Vector) -> &'static str {
+        if !self.vertical_wall_at(coords) {
+            " "
+        } else if coords.on_grid() {
+            "/"
+        } else {
+            "\\"
+        }
+    }
+
+    fn horizontal_glyph(&self, coords: Vector) -> &'static str {
+        if !coords.on_grid() && self.horizontal_wall_at(coords) {
+            "___"
+        } else {
+            "   "
+        }
+    }
+
+    /// Draws the maze in the top-left corner of the canvas, clipped to the
+    /// canvas's size. See [`Style`](crate::style::Style) for other ways to
+    /// draw it.
+    pub fn draw<C: Canvas + ?Sized>(&self, canvas: &mut C) {
+        self.draw_glyphs(canvas, ['/', '\\', '_']);
+    }
+
+    /// Draws the maze like [`Maze::draw`], replacing `/`, `\` and `_` with
+    /// the given characters.
+    pub(crate) fn draw_glyphs<C: Canvas + ?Sized>(&self, canvas: &mut C, glyphs: [char; 3]) {
         if self.cells.is_empty() {
             return;
         }
 
-        let height = self.cells.rows() * 2 + 2;
-        for y in 0..height {
+        let replace = |glyph: &str| -> String {
+            glyph
+                .chars()
+                .map(|ch| match ch {
+                    '/' => glyphs[0],
+                    '\\' => glyphs[1],
+                    '_' => glyphs[2],
+                    ch => ch,
+                })
+                .collect()
+        };
+        let cols = self.cells.cols() as i16;
+        for y in 0..self.cells.rows() * 2 + 2 {
             let half_row = y as i16 - 1;
-            term.goto(0, y);
+            let mut x = 0;
+            for col in 0..=cols {
+                let coords = Vector { col, half_row };
+                let glyph = replace(self.vertical_glyph(coords));
+                canvas.write_str(x, y, &glyph, CLEAR_COLOR, CLEAR_COLOR);
+                x = x.saturating_add(1);
+                if col == cols {
+                    break;
+                }
+
+                let background = self
+                    .cells
+                    .get(coords.nearest_north())
+                    .map_or(CLEAR_COLOR, |cell| cell.background);
+                let glyph = replace(self.horizontal_glyph(coords));
+                canvas.write_str(x, y, &glyph, CLEAR_COLOR, background);
+                x = x.saturating_add(3);
+            }
+        }
+    }
 
+    /// Renders the maze as plain text, in the same form as [`Maze::draw`] but
+    /// without colors. Trailing whitespace is trimmed from each line.
+    ///
+    /// ```
+    /// # use hex_maze::maze::Maze;
+    /// assert_eq!(
+    ///     Maze::with_size(2, 1).to_ascii(),
+    ///     " ___\n/   \\___\n\\___/   \\\n    \\___/\n",
+    /// );
+    /// ```
+    pub fn to_ascii(&self) -> String {
+        let mut ascii = String::new();
+        if self.cells.is_empty() {
+            return ascii;
+        }
+
+        // Only the odd columns reach down to the last line, so a single column
+        // stops a line short.
+        let mut height = self.cells.rows() as i16 * 2 + 2;
+        if self.cells.cols() == 1 {
+            height -= 1;
+        }
+        for y in 0..height {
+            let half_row = y - 1;
+            let mut line = String::new();
             for col in 0..self.cells.cols() as i16 {
                 let coords = Vector { col, half_row };
-                term.sgr().reset();
-                if self.vertical_wall_at(coords) {
+                line.push_str(self.vertical_glyph(coords));
+                line.push_str(self.horizontal_glyph(coords));
+            }
+            let col = self.cells.cols() as i16;
+            line.push_str(self.vertical_glyph(Vector { col, half_row }));
+
+            ascii.push_str(line.trim_end());
+            ascii.push('\n');
+        }
+        ascii
+    }
+
+    /// Parses a maze from the text form produced by [`Maze::to_ascii`].
+    ///
+    /// Leading and trailing blank lines, and trailing whitespace on each line,
+    /// are ignored, except for a blank first line left by an opening in the
+    /// top of the maze.
+    /// Cell backgrounds are reset to [`CLEAR_COLOR`]. Gaps in the outer
+    /// boundary become [`Maze::openings`].
+    pub fn from_ascii(s: &str) -> Result<Self, ParseMazeError> {
+        let mut lines = s
+            .lines()
+            .map(|line| line.trim_end().chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        while lines.last().is_some_and(Vec::is_empty) {
+            lines.pop();
+        }
+        let blank = lines.iter().take_while(|line| line.is_empty()).count();
+        lines.drain(..blank);
+        let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+        if width == 0 {
+            return Ok(Self::empty());
+        }
+        if (width - 1) % 4 != 0 {
+            return Err(ParseMazeError::InvalidSize {
+                width,
+                height: lines.len(),
+            });
+        }
+
+        let cols = (width - 1) / 4;
+        // A single column has no last line, as in [`Maze::to_ascii`].
+        let full_height = |lines: &[Vec<char>]| lines.len() + usize::from(cols == 1);
+        // Only the first line of a maze can be blank, when the tops of the
+        // cells along it are all openings, so keep one blank line if the
+        // rest don't pair up without it.
+        if full_height(&lines) % 2 != 0 && blank > 0 {
+            lines.insert(0, Vec::new());
+        }
+        let height = lines.len();
+        let full_height = full_height(&lines);
+        if full_height % 2 != 0 || full_height < 4 {
+            return Err(ParseMazeError::InvalidSize { width, height });
+        }
+        let rows = (full_height - 2) / 2;
+        if cols > i16::MAX as usize || rows > i16::MAX as usize / 2 {
+            return Err(ParseMazeError::InvalidSize { width, height });
+        }
+
+        let char_at = |x: i16, y: i16| -> char {
+            lines
+                .get(y as usize)
+                .and_then(|line| line.get(x as usize))
+                .copied()
+                .unwrap_or(' ')
+        };
+
+        for (y, line) in lines.iter().enumerate() {
+            let half_row = y as i16 - 1;
+            for (x, &found) in line.iter().enumerate() {
+                let coords = Vector {
+                    col: (x / 4) as i16,
+                    half_row,
+                };
+                let expected = if x % 4 == 0 {
                     if coords.on_grid() {
-                        term.write("/");
+                        '/'
                     } else {
-                        term.write("\\");
+                        '\\'
                     }
+                } else if coords.on_grid() {
+                    ' '
                 } else {
-                    term.write(" ");
+                    '_'
+                };
+                if found != ' ' && found != expected {
+                    return Err(ParseMazeError::UnexpectedChar {
+                        line: y + 1,
+                        column: x + 1,
+                        found,
+                    });
                 }
+            }
+        }
+
+        let mut maze = Self::with_size(cols as u16, rows as u16);
+        for pos in maze.cells.indices() {
+            let coords = Vector::from(pos);
+            let x = coords.col * 4;
+            let y = coords.half_row + 1;
 
-                if let Some(cell) = self.cells.get(coords.nearest_north()) {
-                    term.sgr().bg(cell.background);
+            let south = (1..4).map(|dx| char_at(x + dx, y + 1)).collect::<String>();
+            let cell = &mut maze.cells[pos];
+            cell.north_west = char_at(x, y) == '/';
+            cell.north_east = char_at(x + 4, y) == '\\';
+            cell.south = match south.as_str() {
+                "___" => true,
+                "   " => false,
+                _ => {
+                    return Err(ParseMazeError::PartialWall {
+                        line: y as usize + 2,
+                        column: x as usize + 2,
+                    })
                 }
-                if !coords.on_grid() && self.horizontal_wall_at(coords) {
-                    term.write("___");
+            };
+
+            // Where each side of the cell is drawn, relative to its top-left
+            // corner.
+            let sides = [(1, -1), (4, 0), (4, 1), (1, 1), (0, 1), (0, 0)];
+            for (side, (dx, dy)) in sides.into_iter().enumerate() {
+                if maze.cells.contains(coords + Vector::DIRECTIONS[side]) {
+                    continue;
+                }
+                let drawn = if dx == 1 {
+                    let wall = (1..4).map(|dx| char_at(x + dx, y + dy)).collect::<String>();
+                    match wall.as_str() {
+                        "___" => true,
+                        "   " => false,
+                        _ => {
+                            return Err(ParseMazeError::PartialWall {
+                                line: (y + dy) as usize + 1,
+                                column: x as usize + 2,
+                            })
+                        }
+                    }
                 } else {
-                    term.write("   ");
+                    char_at(x + dx, y + dy) != ' '
+                };
+                if !drawn {
+                    maze.openings.push((coords, side));
+                    if side % 2 == 1 {
+                        // The cell's own flag for this side was read as open.
+                        maze.set_wall_between(coords, coords + Vector::DIRECTIONS[side], true);
+                    }
                 }
-                term.sgr().reset();
             }
+        }
+        Ok(maze)
+    }
+}
 
-            let col = self.cells.cols() as i16;
-            let coords = Vector { col, half_row };
-            term.sgr().reset();
-            if self.vertical_wall_at(coords) {
-                if coords.on_grid() {
-                    term.write("/");
-                } else {
-                    term.write("\\");
+impl FromStr for Maze {
+    type Err = ParseMazeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_ascii(s)
+    }
+}
+
+/// An error from parsing a maze with [`Maze::from_ascii`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseMazeError {
+    /// The drawing is not the size of a whole number of cells.
+    InvalidSize { width: usize, height: usize },
+    /// A character that cannot appear at this position of a drawing.
+    UnexpectedChar {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    /// A horizontal wall that is only partly drawn.
+    PartialWall { line: usize, column: usize },
+}
+
+impl fmt::Display for ParseMazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSize { width, height } => {
+                write!(
+                    f,
+                    "a {width}x{height} drawing is not a whole number of cells"
+                )
+            }
+            Self::UnexpectedChar {
+                line,
+                column,
+                found,
+            } => write!(f, "unexpected {found:?} at line {line}, column {column}"),
+            Self::PartialWall { line, column } => {
+                write!(f, "partial wall at line {line}, column {column}")
+            }
+        }
+    }
+}
+
+impl Error for ParseMazeError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        generator::Algorithm,
+        hex::{Position, Vector},
+        maze::{Maze, ParseMazeError},
+        terminal::{Screen, BLUE, CLEAR_COLOR},
+    };
+
+    const SAMPLE_MAZE: &str = r#"
+ ___     ___
+/   \___/   \
+\___/   \___/
+    \___/
+"#;
+
+    #[test]
+    fn test_to_ascii() {
+        assert_eq!(Maze::with_size(3, 1).to_ascii(), &SAMPLE_MAZE[1..]);
+        assert_eq!(Maze::empty().to_ascii(), "");
+        assert_eq!(Maze::with_size(1, 1).to_ascii(), " ___\n/   \\\n\\___/\n");
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut maze = Maze::with_size(3, 1);
+        maze.cells[Vector::TWO_EAST].background = BLUE;
+
+        let mut screen = Screen::new(20, 5);
+        maze.draw(&mut screen);
+        assert_eq!(screen.text(), format!("{}\n", &SAMPLE_MAZE[1..]));
+
+        // Only the insides of the cell are colored, not its walls.
+        let background = |x, y| screen.get(x, y).unwrap().bg;
+        assert_eq!(background(9, 0), CLEAR_COLOR);
+        assert_eq!(background(9, 1), BLUE);
+        assert_eq!(background(12, 1), CLEAR_COLOR);
+        assert_eq!(background(11, 2), BLUE);
+        assert_eq!(background(5, 2), CLEAR_COLOR);
+    }
+
+    #[test]
+    fn test_draw_clipped() {
+        let mut screen = Screen::new(7, 2);
+        Maze::with_size(3, 1).draw(&mut screen);
+        assert_eq!(screen.text(), " ___\n/   \\__\n");
+    }
+
+    #[test]
+    fn test_from_ascii() {
+        assert_eq!(Maze::from_ascii(SAMPLE_MAZE), Ok(Maze::with_size(3, 1)));
+        assert_eq!(Maze::from_ascii(""), Ok(Maze::empty()));
+    }
+
+    #[test]
+    fn test_ascii_round_trip() {
+        for algorithm in Algorithm::ALL {
+            for (cols, rows) in [(1, 1), (1, 4), (5, 1), (8, 6), (13, 9)] {
+                let mut maze = algorithm.generator(cols, rows, 3).finish().clone();
+                for pos in maze.cells.indices() {
+                    maze.cells[pos].background = CLEAR_COLOR;
                 }
-            } else {
-                term.write(" ");
+
+                let ascii = maze.to_ascii();
+                assert_eq!(Maze::from_ascii(&ascii), Ok(maze));
+                assert_eq!(Maze::from_ascii(&ascii).unwrap().to_ascii(), ascii);
             }
         }
     }
+
+    #[test]
+    fn test_single_column_round_trip() {
+        for rows in 1..6 {
+            let mut maze = Algorithm::Dfs.generator(1, rows, 3).finish().clone();
+            maze.clear_backgrounds();
+
+            let ascii = maze.to_ascii();
+            assert!(!ascii.ends_with("\n\n"), "{ascii:?}");
+            assert_eq!(Maze::from_ascii(&ascii), Ok(maze.clone()));
+            // Editors often strip the final newline, or add blank lines.
+            assert_eq!(Maze::from_ascii(ascii.trim_end()), Ok(maze.clone()));
+            assert_eq!(Maze::from_ascii(&format!("{ascii}\n\n")), Ok(maze));
+        }
+        assert_eq!(
+            Maze::from_ascii(Maze::with_size(4, 2).to_ascii().trim_end()),
+            Ok(Maze::with_size(4, 2))
+        );
+    }
+
+    #[test]
+    fn test_openings() {
+        let mut maze = Maze::with_size(3, 2);
+        let bottom_right = Vector::from(Position { col: 2, row: 1 });
+        assert_eq!(maze.cut_opening(Vector::ZERO), Some(5));
+        assert_eq!(maze.cut_opening(bottom_right), Some(2));
+        // Only cells on the edge can have openings.
+        assert_eq!(
+            Maze::with_size(3, 3).cut_opening(Vector::SOUTH_EAST + Vector::SOUTH),
+            None
+        );
+
+        let ascii = maze.to_ascii();
+        assert_eq!(
+            ascii,
+            r#" ___     ___
+    \___/   \
+\___/   \___/
+/   \___/   \
+\___/   \___
+    \___/
+"#
+        );
+        assert_eq!(Maze::from_ascii(&ascii), Ok(maze.clone()));
+
+        // An opening in the top of a single column leaves the first line
+        // blank.
+        for rows in 2..5 {
+            let mut column = Maze::with_size(1, rows);
+            assert_eq!(column.cut_opening(Vector::ZERO), Some(0));
+            let ascii = column.to_ascii();
+            assert!(ascii.starts_with('\n'));
+            assert_eq!(Maze::from_ascii(&ascii), Ok(column.clone()));
+            assert_eq!(Maze::from_ascii(&format!("\n\n{ascii}")), Ok(column));
+        }
+
+        // The openings are drawn, but can't be walked through.
+        assert!(maze.wall_between(Vector::ZERO, Vector::NORTH_WEST));
+        assert!(!maze.is_wall_drawn(Vector::ZERO, Vector::NORTH_WEST));
+        assert_eq!(
+            maze.walls().count(),
+            Maze::with_size(3, 2).walls().count() - 2
+        );
+    }
+
+    #[test]
+    fn test_position_at() {
+        let maze = Maze::with_size(3, 2);
+        assert_eq!(maze.position_at(1, 1), Some(Position { col: 0, row: 0 }));
+        assert_eq!(maze.position_at(3, 2), Some(Position { col: 0, row: 0 }));
+        assert_eq!(maze.position_at(2, 3), Some(Position { col: 0, row: 1 }));
+        assert_eq!(maze.position_at(6, 1), None);
+        assert_eq!(maze.position_at(6, 2), Some(Position { col: 1, row: 0 }));
+        assert_eq!(maze.position_at(4, 2), None);
+        assert_eq!(maze.position_at(1, 0), None);
+        assert_eq!(maze.position_at(13, 2), None);
+
+        // Every cell is found where it is drawn.
+        for pos in maze.cells.indices() {
+            let coords = Vector::from(pos);
+            let x = coords.col as u16 * 4 + 2;
+            let y = coords.half_row as u16 + 1;
+            assert_eq!(maze.position_at(x, y), Some(pos));
+            assert_eq!(maze.position_at(x, y + 1), Some(pos));
+        }
+    }
+
+    #[test]
+    fn test_wall_at() {
+        let maze = Maze::with_size(3, 2);
+        let south_east = Vector::SOUTH_EAST;
+        assert_eq!(maze.wall_at(2, 2), Some((Vector::ZERO, Vector::SOUTH)));
+        assert_eq!(maze.wall_at(4, 3), Some((Vector::SOUTH, south_east)));
+        assert_eq!(maze.wall_at(4, 2), Some((Vector::ZERO, south_east)));
+        assert_eq!(maze.wall_at(2, 1), None);
+        // Walls on the boundary have a cell on only one side.
+        assert_eq!(maze.wall_at(0, 1), None);
+        assert_eq!(maze.wall_at(2, 0), None);
+    }
+
+    #[test]
+    fn test_from_ascii_errors() {
+        assert_eq!(
+            Maze::from_ascii(" ___\n/   \\\n"),
+            Err(ParseMazeError::InvalidSize {
+                width: 5,
+                height: 2
+            })
+        );
+        assert_eq!(
+            Maze::from_ascii(" ___\n/   \\__\n\\___/\n\n"),
+            Err(ParseMazeError::InvalidSize {
+                width: 7,
+                height: 3
+            })
+        );
+        assert_eq!(
+            Maze::from_ascii(" ___\n/ x \\\n\\___/\n\n"),
+            Err(ParseMazeError::UnexpectedChar {
+                line: 2,
+                column: 3,
+                found: 'x'
+            })
+        );
+        assert_eq!(
+            Maze::from_ascii(" ___\n/   \\\n\\_ _/\n\n"),
+            Err(ParseMazeError::PartialWall { line: 3, column: 2 })
+        );
+    }
 }
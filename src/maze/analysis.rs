@@ -0,0 +1,272 @@
+use std::fmt;
+
+use crate::{
+    grid::HexGrid,
+    hex::{Position, Vector},
+    maze::Maze,
+    solver,
+};
+
+/// Statistics describing the shape of a maze, for tuning generators and
+/// rejecting boring mazes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub cells: usize,
+    /// The number of cells with each number of open sides, from 0 to 6.
+    pub degrees: [usize; 7],
+    /// The number of corridors of each length, indexed by length. A corridor
+    /// is a run of cells that each have exactly two open sides.
+    pub corridor_lengths: Vec<usize>,
+    /// The number of moves from start to goal, if the goal can be reached.
+    pub solution_length: Option<usize>,
+    /// The hex distance from start to goal, ignoring walls.
+    pub straight_distance: usize,
+    /// The most moves needed to get between any two cells that are connected,
+    /// if [`diameter_is_exact`](Self::diameter_is_exact). Otherwise, the
+    /// maze has loops and this is only a lower bound.
+    pub diameter: usize,
+    /// Whether the maze is perfect, so that the diameter could be found
+    /// exactly.
+    pub diameter_is_exact: bool,
+}
+
+impl Analysis {
+    /// Analyzes a maze solved from the top-left corner to the bottom-right
+    /// corner.
+    pub fn new(maze: &Maze) -> Self {
+        let goal = Vector::from(Position {
+            col: maze.cells.cols() as i16 - 1,
+            row: maze.cells.rows() as i16 - 1,
+        });
+        Self::with_endpoints(maze, Vector::ZERO, goal)
+    }
+
+    pub fn with_endpoints(maze: &Maze, start: Vector, goal: Vector) -> Self {
+        let mut degrees = [0; 7];
+        for pos in maze.cells.indices() {
+            degrees[maze.degree(pos.into())] += 1;
+        }
+
+        Self {
+            cells: maze.cells.indices().count(),
+            degrees,
+            corridor_lengths: corridor_lengths(maze),
+            solution_length: solver::distances(maze, start)
+                .get(goal)
+                .copied()
+                .flatten()
+                .map(|moves| moves as usize),
+            straight_distance: (goal - start).distance() as usize,
+            diameter: diameter(maze),
+            diameter_is_exact: maze.is_perfect(),
+        }
+    }
+
+    /// Cells with only one open side.
+    pub fn dead_ends(&self) -> usize {
+        self.degrees[1]
+    }
+
+    /// Cells with three or more open sides, where the route branches.
+    pub fn junctions(&self) -> usize {
+        self.degrees[3..].iter().sum()
+    }
+
+    /// The fraction of cells that lie in corridors. Mazes with a high river
+    /// factor flow along long winding passages, while a low one means many
+    /// short branches and dead ends.
+    pub fn river(&self) -> f64 {
+        if self.cells == 0 {
+            0.0
+        } else {
+            self.degrees[2] as f64 / self.cells as f64
+        }
+    }
+
+    /// The number of cells on the shortest route from start to goal,
+    /// including both.
+    pub fn solution_cells(&self) -> Option<usize> {
+        self.solution_length.map(|moves| moves + 1)
+    }
+
+    /// How much longer the solution is than a straight line from start to
+    /// goal.
+    pub fn tortuosity(&self) -> Option<f64> {
+        let moves = self.solution_length?;
+        (self.straight_distance > 0).then(|| moves as f64 / self.straight_distance as f64)
+    }
+
+    /// The average length of a corridor, in cells.
+    pub fn mean_corridor_length(&self) -> f64 {
+        let count: usize = self.corridor_lengths.iter().sum();
+        let total: usize = self
+            .corridor_lengths
+            .iter()
+            .enumerate()
+            .map(|(length, count)| length * count)
+            .sum();
+        if count == 0 {
+            0.0
+        } else {
+            total as f64 / count as f64
+        }
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "cells:           {}", self.cells)?;
+        writeln!(f, "dead ends:       {}", self.dead_ends())?;
+        let by_degree: Vec<_> = (3..7)
+            .filter(|&degree| self.degrees[degree] > 0)
+            .map(|degree| format!("{} with {degree} ways", self.degrees[degree]))
+            .collect();
+        if by_degree.is_empty() {
+            writeln!(f, "junctions:       0")?;
+        } else {
+            writeln!(
+                f,
+                "junctions:       {} ({})",
+                self.junctions(),
+                by_degree.join(", ")
+            )?;
+        }
+        writeln!(f, "river factor:    {:.3}", self.river())?;
+        writeln!(
+            f,
+            "corridors:       {} (mean length {:.2}, longest {})",
+            self.corridor_lengths.iter().sum::<usize>(),
+            self.mean_corridor_length(),
+            self.corridor_lengths.len().saturating_sub(1),
+        )?;
+        match (self.solution_length, self.solution_cells()) {
+            (Some(moves), Some(cells)) => {
+                writeln!(f, "solution:        {moves} moves through {cells} cells")?;
+            }
+            _ => writeln!(f, "solution:        unreachable")?,
+        }
+        if let Some(tortuosity) = self.tortuosity() {
+            writeln!(f, "tortuosity:      {tortuosity:.2}")?;
+        }
+        if self.diameter_is_exact {
+            writeln!(f, "diameter:        {}", self.diameter)
+        } else {
+            writeln!(f, "diameter:        at least {}", self.diameter)
+        }
+    }
+}
+
+fn corridor_lengths(maze: &Maze) -> Vec<usize> {
+    let mut lengths = Vec::new();
+    let mut seen = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| false);
+    for pos in maze.cells.indices().map(Vector::from) {
+        if seen[pos] || maze.degree(pos) != 2 {
+            continue;
+        }
+
+        // Follow the corridor both ways from here.
+        seen[pos] = true;
+        let mut length = 1;
+        for mut next in maze.open_neighbors(pos) {
+            let mut prev = pos;
+            while maze.cells.contains(next) && !seen[next] && maze.degree(next) == 2 {
+                seen[next] = true;
+                length += 1;
+                let after = maze.open_neighbors(next).find(|&n| n != prev).unwrap();
+                (prev, next) = (next, after);
+            }
+        }
+
+        if lengths.len() <= length {
+            lengths.resize(length + 1, 0);
+        }
+        lengths[length] += 1;
+    }
+    lengths
+}
+
+/// The longest shortest route between two cells, found with two
+/// breadth-first sweeps: the farthest cell from the top-left corner, then the
+/// farthest cell from that.
+///
+/// In a perfect maze, which is a spanning tree, the first sweep always ends
+/// at one end of a longest route, so this is exact. With loops it can fall
+/// short, but finding the exact diameter would mean a sweep from every cell.
+fn diameter(maze: &Maze) -> usize {
+    let Some((end, _)) = solver::farthest(maze, Vector::ZERO) else {
+        return 0;
+    };
+    solver::farthest(maze, end).map_or(0, |(_, distance)| distance as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        generator::Algorithm,
+        hex::Vector,
+        maze::{
+            analysis::Analysis,
+            fixtures::{branch, BOTTOM},
+        },
+    };
+
+    #[test]
+    fn test_branch() {
+        let maze = branch();
+        let analysis = Analysis::with_endpoints(&maze, Vector::ZERO, BOTTOM);
+        assert_eq!(analysis.cells, 6);
+        assert_eq!(analysis.degrees, [2, 3, 0, 1, 0, 0, 0]);
+        assert_eq!(analysis.dead_ends(), 3);
+        assert_eq!(analysis.junctions(), 1);
+        assert_eq!(analysis.river(), 0.0);
+        assert_eq!(analysis.corridor_lengths, Vec::<usize>::new());
+        assert_eq!(analysis.solution_length, Some(2));
+        assert_eq!(analysis.solution_cells(), Some(3));
+        assert_eq!(analysis.tortuosity(), Some(1.0));
+        // The isolated cells are ignored.
+        assert_eq!(analysis.diameter, 2);
+        assert!(!analysis.diameter_is_exact);
+        assert!(analysis.to_string().contains("diameter:        at least 2"));
+
+        // The far corner can't be reached.
+        assert_eq!(Analysis::new(&maze).solution_length, None);
+    }
+
+    #[test]
+    fn test_corridors() {
+        let mut maze = branch();
+        maze.set_wall_between(Vector::SOUTH, Vector::SOUTH + Vector::SOUTH_EAST, true);
+        maze.set_wall_between(BOTTOM, BOTTOM + Vector::NORTH_EAST, false);
+
+        let analysis = Analysis::with_endpoints(&maze, Vector::ZERO, BOTTOM);
+        assert_eq!(analysis.corridor_lengths, vec![0, 0, 1]);
+        assert_eq!(analysis.mean_corridor_length(), 2.0);
+        assert_eq!(analysis.river(), 2.0 / 6.0);
+        assert_eq!(analysis.diameter, 3);
+    }
+
+    #[test]
+    fn test_generated() {
+        for algorithm in Algorithm::ALL {
+            let analysis = Analysis::new(algorithm.generator(12, 8, 3).finish());
+
+            // Every cell of a spanning tree is counted once by its degree,
+            // and the degrees add up to twice the number of passages.
+            assert_eq!(analysis.degrees.iter().sum::<usize>(), analysis.cells);
+            let degree_sum: usize = analysis
+                .degrees
+                .iter()
+                .enumerate()
+                .map(|(degree, count)| degree * count)
+                .sum();
+            assert_eq!(degree_sum, 2 * (analysis.cells - 1));
+
+            let solution = analysis.solution_length.unwrap();
+            assert!(solution >= analysis.straight_distance);
+            assert!(analysis.diameter >= solution);
+            assert!(analysis.diameter_is_exact);
+            assert!(analysis.tortuosity().unwrap() >= 1.0);
+            assert!(!analysis.to_string().is_empty());
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! Small hand-built mazes shared by the tests.
+
+use crate::{hex::Vector, maze::Maze};
+
+/// The bottom cell of the first column of [`corridor`].
+pub const BOTTOM: Vector = Vector {
+    col: 0,
+    half_row: 4,
+};
+
+/// Two columns of three cells, with a straight corridor down the first
+/// column from [`Vector::ZERO`] to [`BOTTOM`].
+pub fn corridor() -> Maze {
+    let mut maze = Maze::with_size(2, 3);
+    maze.set_wall_between(Vector::ZERO, Vector::SOUTH, false);
+    maze.set_wall_between(Vector::SOUTH, BOTTOM, false);
+    maze
+}
+
+/// The [`corridor`], with a dead end off to the side of its middle cell.
+pub fn branch() -> Maze {
+    let mut maze = corridor();
+    maze.set_wall_between(Vector::SOUTH, Vector::SOUTH + Vector::SOUTH_EAST, false);
+    maze
+}
@@ -0,0 +1,127 @@
+use std::collections::VecDeque;
+
+use crate::{
+    grid::HexGrid,
+    hex::{Position, Vector},
+    maze::Maze,
+    solver,
+};
+
+impl Maze {
+    /// The number of open walls between two cells of the maze.
+    pub fn passages(&self) -> usize {
+        self.cells
+            .indices()
+            .map(|pos| self.open_neighbors(pos.into()).count())
+            .sum::<usize>()
+            / 2
+    }
+
+    /// The groups of cells that can be reached from each other.
+    pub fn components(&self) -> Vec<Vec<Position>> {
+        let mut components = Vec::new();
+        let mut seen = HexGrid::new_with(self.cells.cols(), self.cells.rows(), |_| false);
+        for start in self.cells.indices() {
+            if seen[start] {
+                continue;
+            }
+
+            let mut component = Vec::new();
+            seen[start] = true;
+            let mut queue = VecDeque::from([Vector::from(start)]);
+            while let Some(pos) = queue.pop_front() {
+                component.push(pos.into());
+                for neighbor in self.open_neighbors(pos) {
+                    if !seen[neighbor] {
+                        seen[neighbor] = true;
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Whether every cell can be reached from every other.
+    pub fn is_connected(&self) -> bool {
+        self.components().len() <= 1
+    }
+
+    /// The number of independent loops in the maze, i.e. how many walls
+    /// would have to be added to leave no loops.
+    pub fn cycles(&self) -> usize {
+        (self.passages() + self.components().len()).saturating_sub(self.cells.indices().count())
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        self.cycles() > 0
+    }
+
+    /// The cells that cannot be reached from `start`.
+    pub fn unreachable_from(&self, start: Vector) -> Vec<Position> {
+        let distances = solver::distances(self, start);
+        self.cells
+            .indices()
+            .filter(|&pos| distances[pos].is_none())
+            .collect()
+    }
+
+    /// Whether the maze is a spanning tree, with exactly one route between
+    /// any two cells.
+    pub fn is_perfect(&self) -> bool {
+        self.is_connected() && !self.has_cycle()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hex::{Position, Vector},
+        maze::{
+            fixtures::{corridor, BOTTOM},
+            Maze,
+        },
+    };
+
+    #[test]
+    fn test_disconnected() {
+        let maze = corridor();
+        assert_eq!(maze.passages(), 2);
+        assert_eq!(maze.components().len(), 4);
+        assert!(!maze.is_connected());
+        assert!(!maze.has_cycle());
+        assert!(!maze.is_perfect());
+        assert_eq!(
+            maze.unreachable_from(Vector::ZERO),
+            vec![
+                Position { col: 1, row: 0 },
+                Position { col: 1, row: 1 },
+                Position { col: 1, row: 2 },
+            ],
+        );
+        // Starting outside the maze reaches nothing.
+        assert_eq!(maze.unreachable_from(Vector::NORTH).len(), 6);
+    }
+
+    #[test]
+    fn test_perfect() {
+        let mut maze = corridor();
+        for pos in [Vector::ZERO, Vector::SOUTH, BOTTOM] {
+            maze.set_wall_between(pos, pos + Vector::SOUTH_EAST, false);
+        }
+        assert!(maze.is_connected());
+        assert!(maze.is_perfect());
+        assert!(maze.unreachable_from(Vector::ZERO).is_empty());
+
+        maze.set_wall_between(Vector::SOUTH, Vector::SOUTH + Vector::NORTH_EAST, false);
+        assert_eq!(maze.cycles(), 1);
+        assert!(!maze.is_perfect());
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(Maze::empty().is_perfect());
+        assert!(Maze::empty().components().is_empty());
+    }
+}
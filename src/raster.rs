@@ -0,0 +1,308 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::{
+    color::Color,
+    hex::{Vector, HEX_INRADIUS},
+    maze::Maze,
+    terminal::{BLACK, CLEAR_COLOR, PALETTE, RED, WHITE},
+};
+
+/// Maps the 16 basic terminal colors to RGBA values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette(pub [[u8; 4]; 16]);
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self(PALETTE.map(|[r, g, b]| [r, g, b, 0xff]))
+    }
+}
+
+impl Palette {
+    /// The RGBA value of `color`, which is transparent for
+    /// [`Color::Default`].
+    pub fn rgba(&self, color: Color) -> [u8; 4] {
+        match color {
+            Color::Basic(n) | Color::Indexed(n @ 0..16) => self.0[n as usize % 16],
+            _ => match color.rgb() {
+                Some([r, g, b]) => [r, g, b, 0xff],
+                None => [0; 4],
+            },
+        }
+    }
+}
+
+/// Options for rendering a maze with [`render`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RasterOptions {
+    /// The circumradius of each hexagon, in pixels.
+    pub cell_radius: f64,
+    pub wall_width: f64,
+    pub path_width: f64,
+    /// Blank space around the maze, in pixels.
+    pub margin: f64,
+    /// Whether to fill cells with their background color.
+    pub fill_backgrounds: bool,
+    pub palette: Palette,
+    pub background_color: Color,
+    pub wall_color: Color,
+    pub path_color: Color,
+}
+
+impl Default for RasterOptions {
+    fn default() -> Self {
+        Self {
+            cell_radius: 10.0,
+            wall_width: 2.0,
+            path_width: 3.0,
+            margin: 4.0,
+            fill_backgrounds: true,
+            palette: Palette::default(),
+            background_color: WHITE,
+            wall_color: BLACK,
+            path_color: RED,
+        }
+    }
+}
+
+/// An RGBA image, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, color: [u8; 4]) -> Self {
+        let pixels = color.repeat(width as usize * height as usize);
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The raw RGBA bytes of the image.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = self.offset(x, y);
+        self.pixels[i..i + 4].try_into().unwrap()
+    }
+
+    fn offset(&self, x: u32, y: u32) -> usize {
+        assert!(x < self.width && y < self.height, "pixel out of bounds");
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+
+    /// Blends `color` over the pixel, with `coverage` between 0 and 1 scaling
+    /// the color's alpha.
+    fn blend(&mut self, x: u32, y: u32, color: [u8; 4], coverage: f64) {
+        let alpha = coverage.clamp(0.0, 1.0) * color[3] as f64 / 255.0;
+        let i = self.offset(x, y);
+        let pixel = &mut self.pixels[i..i + 4];
+        for c in 0..3 {
+            pixel[c] = (color[c] as f64 * alpha + pixel[c] as f64 * (1.0 - alpha)).round() as u8;
+        }
+        pixel[3] = (255.0 * alpha + pixel[3] as f64 * (1.0 - alpha)).round() as u8;
+    }
+
+    /// Calls `coverage` for each pixel center within the bounding box, and
+    /// blends `color` into pixels where it returns a positive value.
+    fn fill(
+        &mut self,
+        (min_x, min_y): (f64, f64),
+        (max_x, max_y): (f64, f64),
+        color: [u8; 4],
+        coverage: impl Fn(f64, f64) -> f64,
+    ) {
+        let x_range = min_x.floor().max(0.0) as u32..(max_x.ceil().max(0.0) as u32).min(self.width);
+        let y_range =
+            min_y.floor().max(0.0) as u32..(max_y.ceil().max(0.0) as u32).min(self.height);
+        for y in y_range {
+            for x in x_range.clone() {
+                let coverage = coverage(x as f64 + 0.5, y as f64 + 0.5);
+                if coverage > 0.0 {
+                    self.blend(x, y, color, coverage);
+                }
+            }
+        }
+    }
+
+    /// Draws an anti-aliased line with round ends.
+    fn line(&mut self, (x1, y1): (f64, f64), (x2, y2): (f64, f64), width: f64, color: [u8; 4]) {
+        let r = width / 2.0;
+        self.fill(
+            (x1.min(x2) - r - 1.0, y1.min(y2) - r - 1.0),
+            (x1.max(x2) + r + 1.0, y1.max(y2) + r + 1.0),
+            color,
+            |x, y| r + 0.5 - distance_to_segment((x, y), (x1, y1), (x2, y2)),
+        );
+    }
+
+    /// Fills the flat-topped hexagon centered at `(cx, cy)`.
+    fn hexagon(&mut self, (cx, cy): (f64, f64), radius: f64, color: [u8; 4]) {
+        let inradius = radius * HEX_INRADIUS;
+        self.fill(
+            (cx - radius, cy - inradius),
+            (cx + radius, cy + inradius),
+            color,
+            |x, y| {
+                let dx = (x - cx).abs();
+                let dy = (y - cy).abs();
+                // Distance inside the nearest edge: either the flat top and
+                // bottom or one of the slanted sides.
+                let inside = (inradius - dy).min(inradius - (dx * HEX_INRADIUS + dy * 0.5));
+                inside + 0.5
+            },
+        );
+    }
+
+    pub fn write_png(&self, writer: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(io::Error::other)?;
+        writer.finish().map_err(io::Error::other)
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_png(&mut file)?;
+        file.flush()
+    }
+}
+
+fn distance_to_segment((px, py): (f64, f64), (x1, y1): (f64, f64), (x2, y2): (f64, f64)) -> f64 {
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((px - x1) * dx + (py - y1) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    let (nx, ny) = (x1 + t * dx, y1 + t * dy);
+    ((px - nx).powi(2) + (py - ny).powi(2)).sqrt()
+}
+
+/// Rasterizes a maze, optionally with a path through it such as
+/// [`Solver::path`](crate::solver::Solver::path).
+pub fn render(maze: &Maze, path: Option<&[Vector]>, options: &RasterOptions) -> Image {
+    let radius = options.cell_radius;
+    let margin = options.margin;
+    let palette = &options.palette;
+    let (width, height) = maze.extent(radius);
+    let point = |(x, y): (f64, f64)| (x + margin, y + margin);
+
+    let mut image = Image::new(
+        (width + 2.0 * margin).ceil() as u32,
+        (height + 2.0 * margin).ceil() as u32,
+        palette.rgba(options.background_color),
+    );
+
+    if options.fill_backgrounds {
+        for pos in maze.cells.indices() {
+            let background = maze.cells[pos].background;
+            if background != CLEAR_COLOR {
+                let center = point(Vector::from(pos).center(radius));
+                image.hexagon(center, radius, palette.rgba(background));
+            }
+        }
+    }
+
+    let wall_color = palette.rgba(options.wall_color);
+    for (pos, side) in maze.walls() {
+        let corners = pos.corners(radius).map(point);
+        image.line(
+            corners[(side + 4) % 6],
+            corners[(side + 5) % 6],
+            options.wall_width,
+            wall_color,
+        );
+    }
+
+    if let Some(path) = path {
+        let path_color = palette.rgba(options.path_color);
+        for segment in path.windows(2) {
+            image.line(
+                point(segment[0].center(radius)),
+                point(segment[1].center(radius)),
+                options.path_width,
+                path_color,
+            );
+        }
+    }
+
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hex::Vector,
+        maze::Maze,
+        raster::{render, Palette, RasterOptions},
+        terminal::{BLACK, GREEN, RED, WHITE},
+    };
+
+    #[test]
+    fn test_render_single_cell() {
+        let options = RasterOptions::default();
+        let image = render(&Maze::with_size(1, 1), None, &options);
+        assert_eq!((image.width(), image.height()), (28, 26));
+
+        let palette = Palette::default();
+        assert_eq!(image.pixel(0, 0), palette.rgba(WHITE));
+        assert_eq!(image.pixel(14, 13), palette.rgba(WHITE));
+        // The middle of the top wall.
+        assert_eq!(image.pixel(14, 4), palette.rgba(BLACK));
+    }
+
+    #[test]
+    fn test_render_backgrounds_and_path() {
+        let mut maze = Maze::with_size(2, 1);
+        maze.set_wall_between(Vector::ZERO, Vector::SOUTH_EAST, false);
+        maze.cells[Vector::ZERO].background = GREEN;
+        let options = RasterOptions::default();
+        let palette = options.palette;
+
+        let image = render(&maze, None, &options);
+        assert_eq!(image.pixel(10, 13), palette.rgba(GREEN));
+
+        let path = [Vector::ZERO, Vector::SOUTH_EAST];
+        let image = render(&maze, Some(&path), &options);
+        assert_eq!(image.pixel(14, 13), palette.rgba(RED));
+        assert_eq!(image.pixel(29, 21), palette.rgba(RED));
+    }
+
+    #[test]
+    fn test_write_png() {
+        let image = render(&Maze::with_size(3, 2), None, &RasterOptions::default());
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+
+        let decoder = png::Decoder::new(png.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (image.width(), image.height()));
+        assert_eq!(&pixels[..info.buffer_size()], image.pixels());
+    }
+}
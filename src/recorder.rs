@@ -0,0 +1,253 @@
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+use gif::{Encoder, Frame, Repeat};
+
+use crate::{
+    generator::MazeGenerator,
+    hex::Vector,
+    maze::Maze,
+    raster::{self, RasterOptions},
+    solver,
+};
+
+/// Options for recording an animation with [`GifRecorder`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GifOptions {
+    pub raster: RasterOptions,
+    /// The number of steps to skip between recorded frames.
+    pub frame_skip: u32,
+    /// How long each frame is shown for. GIFs store this in hundredths of a
+    /// second.
+    pub frame_delay: Duration,
+    /// How long the final frame is shown for before the animation repeats.
+    pub final_delay: Duration,
+}
+
+impl Default for GifOptions {
+    fn default() -> Self {
+        Self {
+            raster: RasterOptions::default(),
+            frame_skip: 0,
+            frame_delay: Duration::from_millis(20),
+            final_delay: Duration::from_secs(3),
+        }
+    }
+}
+
+/// Writes frames of a maze into an animated GIF.
+pub struct GifRecorder<W: Write> {
+    encoder: Encoder<W>,
+    options: GifOptions,
+    width: u16,
+    height: u16,
+    steps: u32,
+}
+
+impl<W: Write> GifRecorder<W> {
+    /// Starts an animation sized to fit `maze`. Every frame must be of a maze
+    /// with the same dimensions.
+    pub fn new(writer: W, maze: &Maze, options: GifOptions) -> io::Result<Self> {
+        let image = raster::render(maze, None, &options.raster);
+        let (Ok(width), Ok(height)) = (image.width().try_into(), image.height().try_into()) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "maze is too large for a GIF",
+            ));
+        };
+
+        let mut encoder = Encoder::new(writer, width, height, &[]).map_err(io::Error::other)?;
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        Ok(Self {
+            encoder,
+            options,
+            width,
+            height,
+            steps: 0,
+        })
+    }
+
+    /// Records a frame after a step of an algorithm, unless it falls within
+    /// the frames being skipped.
+    pub fn step(&mut self, maze: &Maze) -> io::Result<()> {
+        let skip = !self
+            .steps
+            .is_multiple_of(self.options.frame_skip.saturating_add(1));
+        self.steps += 1;
+        if skip {
+            Ok(())
+        } else {
+            self.frame(maze, None, self.options.frame_delay)
+        }
+    }
+
+    /// Records a frame, regardless of frame skipping.
+    pub fn frame(
+        &mut self,
+        maze: &Maze,
+        path: Option<&[Vector]>,
+        delay: Duration,
+    ) -> io::Result<()> {
+        let image = raster::render(maze, path, &self.options.raster);
+        let mut pixels = image.pixels().to_vec();
+        let mut frame = Frame::from_rgba_speed(self.width, self.height, &mut pixels, 10);
+        frame.delay = (delay.as_millis() / 10).try_into().unwrap_or(u16::MAX);
+        self.encoder.write_frame(&frame).map_err(io::Error::other)
+    }
+
+    pub fn finish(self) -> io::Result<W> {
+        self.encoder.into_inner()
+    }
+}
+
+/// Records a generator building its maze, `algorithm` solving it from
+/// `start` to `goal`, and the solution, as an animated GIF.
+///
+/// An empty maze has nothing to solve, so it is recorded as a single frame.
+pub fn record<W: Write>(
+    mut generator: Box<dyn MazeGenerator>,
+    algorithm: solver::Algorithm,
+    start: Vector,
+    goal: Vector,
+    writer: W,
+    options: GifOptions,
+) -> io::Result<W> {
+    let mut recorder = GifRecorder::new(writer, generator.maze(), options)?;
+
+    if generator.maze().cells.is_empty() {
+        recorder.frame(generator.maze(), None, options.final_delay)?;
+        return recorder.finish();
+    }
+
+    recorder.frame(generator.maze(), None, options.frame_delay)?;
+    while !generator.is_done() {
+        generator.step();
+        recorder.step(generator.maze())?;
+    }
+
+    let mut solver = algorithm.solver(generator.maze().clone(), start, goal);
+    while !solver.is_done() {
+        solver.step();
+        recorder.step(solver.maze())?;
+    }
+
+    let mut maze = solver.maze().clone();
+    maze.clear_backgrounds();
+    recorder.frame(&maze, Some(solver.path()), options.final_delay)?;
+
+    recorder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        generator::Algorithm,
+        hex::{Position, Vector},
+        recorder::{record, GifOptions},
+        solver,
+    };
+
+    fn decode_delays(gif: &[u8]) -> Vec<u16> {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(gif).unwrap();
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+        delays
+    }
+
+    /// Records a maze of the given size solved with A* from corner to corner.
+    fn record_corners(generator: Algorithm, cols: u16, rows: u16, options: GifOptions) -> Vec<u8> {
+        let goal = Vector::from(Position {
+            col: cols as i16 - 1,
+            row: rows as i16 - 1,
+        });
+        record(
+            generator.generator(cols, rows, 0),
+            solver::Algorithm::AStar,
+            Vector::ZERO,
+            goal,
+            Vec::new(),
+            options,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_record() {
+        let options = GifOptions {
+            frame_delay: Duration::from_millis(50),
+            final_delay: Duration::from_secs(2),
+            ..GifOptions::default()
+        };
+        let gif = record_corners(Algorithm::Dfs, 3, 2, options);
+
+        let delays = decode_delays(&gif);
+        assert!(delays.len() > 2);
+        assert!(delays[..delays.len() - 1].iter().all(|&delay| delay == 5));
+        assert_eq!(delays.last(), Some(&200));
+    }
+
+    #[test]
+    fn test_record_frame_skip() {
+        let all = record_corners(Algorithm::Prim, 4, 3, GifOptions::default());
+        let skipped = record_corners(
+            Algorithm::Prim,
+            4,
+            3,
+            GifOptions {
+                frame_skip: 3,
+                ..GifOptions::default()
+            },
+        );
+
+        let all = decode_delays(&all).len();
+        let skipped = decode_delays(&skipped).len();
+        assert!(skipped < all);
+        // The first and last frames are always kept.
+        assert_eq!(skipped, (all - 2).div_ceil(4) + 2);
+
+        let most = GifOptions {
+            frame_skip: u32::MAX,
+            ..GifOptions::default()
+        };
+        let most = record_corners(Algorithm::Prim, 4, 3, most);
+        assert_eq!(decode_delays(&most).len(), 3);
+    }
+
+    #[test]
+    fn test_record_solver() {
+        let frames = |algorithm: solver::Algorithm| {
+            let start = Vector::from(Position { col: 2, row: 1 });
+            let generator = Algorithm::Dfs.generator(5, 4, 0);
+            let options = GifOptions::default();
+            let gif = record(
+                generator,
+                algorithm,
+                start,
+                Vector::ZERO,
+                Vec::new(),
+                options,
+            );
+            decode_delays(&gif.unwrap()).len()
+        };
+        assert_ne!(
+            frames(solver::Algorithm::Bfs),
+            frames(solver::Algorithm::DeadEndFilling)
+        );
+    }
+
+    #[test]
+    fn test_record_empty() {
+        let gif = record_corners(Algorithm::Dfs, 0, 0, GifOptions::default());
+        assert_eq!(decode_delays(&gif), [300]);
+    }
+}
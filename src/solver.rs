@@ -1,20 +1,148 @@
-use std::collections::BinaryHeap;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    fmt,
+    str::FromStr,
+};
 
 use crate::{
     grid::HexGrid,
     hex::{Position, Vector},
     maze::Maze,
-    terminal::{CLEAR_COLOR, GREEN, RED},
+    terminal::{GREEN, LIGHT_GREY, RED},
+    terrain::Terrain,
 };
 
-/// Solves a maze using A*.
+pub mod bfs;
+pub mod dead_end_filling;
+pub mod dfs;
+pub mod route;
+pub mod tremaux;
+pub mod wall_follower;
+
+pub use bfs::BfsSolver;
+pub use dead_end_filling::DeadEndFiller;
+pub use dfs::DfsSolver;
+pub use route::{best_order, RouteSolver, MAX_BEST_ORDER};
+pub use tremaux::TremauxSolver;
+pub use wall_follower::{Hand, WallFollower};
+
+/// A maze solving algorithm that can be run one step at a time.
+pub trait MazeSolver {
+    /// The maze, with backgrounds showing the algorithm's progress.
+    fn maze(&self) -> &Maze;
+
+    /// Advances the algorithm by one step. Does nothing once done.
+    fn step(&mut self);
+
+    fn is_done(&self) -> bool;
+
+    /// The route found, from the goal back to the start. Empty until done,
+    /// or if the goal can't be reached.
+    fn path(&self) -> &[Vector];
+
+    /// The cells the algorithm is about to explore.
+    fn frontier(&self) -> Vec<Vector>;
+
+    /// The cells the algorithm has explored so far.
+    fn visited(&self) -> &HexGrid<bool>;
+}
+
+/// The available solving algorithms, for selecting one at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    #[default]
+    AStar,
+    Dijkstra,
+    Bfs,
+    Dfs,
+    LeftHand,
+    RightHand,
+    Tremaux,
+    DeadEndFilling,
+}
+
+impl Algorithm {
+    pub const ALL: [Self; 8] = [
+        Self::AStar,
+        Self::Dijkstra,
+        Self::Bfs,
+        Self::Dfs,
+        Self::LeftHand,
+        Self::RightHand,
+        Self::Tremaux,
+        Self::DeadEndFilling,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::AStar => "astar",
+            Self::Dijkstra => "dijkstra",
+            Self::Bfs => "bfs",
+            Self::Dfs => "dfs",
+            Self::LeftHand => "left-hand",
+            Self::RightHand => "right-hand",
+            Self::Tremaux => "tremaux",
+            Self::DeadEndFilling => "dead-end-fill",
+        }
+    }
+
+    /// The algorithm after this one, wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&a| a == self).unwrap();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn solver(self, maze: Maze, start: Vector, goal: Vector) -> Box<dyn MazeSolver> {
+        match self {
+            Self::AStar => Box::new(Solver::with_endpoints(maze, start, goal)),
+            Self::Dijkstra => Box::new(Solver::dijkstra(maze, start, goal)),
+            Self::Bfs => Box::new(BfsSolver::new(maze, start, goal)),
+            Self::Dfs => Box::new(DfsSolver::new(maze, start, goal)),
+            Self::LeftHand => Box::new(WallFollower::new(maze, start, goal, Hand::Left)),
+            Self::RightHand => Box::new(WallFollower::new(maze, start, goal, Hand::Right)),
+            Self::Tremaux => Box::new(TremauxSolver::new(maze, start, goal)),
+            Self::DeadEndFilling => Box::new(DeadEndFiller::new(maze, start, goal)),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|a| a.name() == s)
+            .ok_or_else(|| format!("unknown solver {s:?}"))
+    }
+}
+
+/// Solves a maze using A*, or Dijkstra's algorithm when made with
+/// [`Solver::dijkstra`].
+///
+/// There can be several goals, in which case it finds a shortest route to
+/// whichever is nearest.
+///
+/// Routes are measured by the [`Terrain::cost`] of each cell stepped into,
+/// so the cheapest route can take more steps than the shortest.
 pub struct Solver {
     pub maze: Maze,
     pub is_done: bool,
     pub path: Vec<Vector>,
-    goal: Vector,
+    goals: Vec<Vector>,
+    heuristic: bool,
+    /// The cost of the cheapest terrain in the maze.
+    cheapest: i32,
     unvisited: BinaryHeap<Unvisited>,
     distances: HexGrid<i32>,
+    visited: HexGrid<bool>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -40,12 +168,22 @@ impl Ord for Unvisited {
 }
 
 impl Solver {
+    /// Solves from the top-left corner to the bottom-right corner.
     pub fn new(maze: Maze) -> Self {
-        let position = Vector::ZERO;
         let goal = Vector::from(Position {
             col: maze.cells.cols() as i16 - 1,
             row: maze.cells.rows() as i16 - 1,
         });
+        Self::with_endpoints(maze, Vector::ZERO, goal)
+    }
+
+    pub fn with_endpoints(maze: Maze, start: Vector, goal: Vector) -> Self {
+        Self::with_goals(maze, start, vec![goal])
+    }
+
+    /// Solves from `start` to whichever of `goals` is nearest.
+    pub fn with_goals(maze: Maze, start: Vector, goals: Vec<Vector>) -> Self {
+        let position = start;
 
         let mut unvisited = BinaryHeap::new();
         unvisited.push(Unvisited {
@@ -54,28 +192,64 @@ impl Solver {
             position,
         });
 
+        // A start outside the maze, as in an empty one, has no route anywhere.
+        let is_done = !maze.cells.contains(position);
         let mut distances = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| i32::MAX);
-        distances[position] = 0;
+        if let Some(distance) = distances.get_mut(position) {
+            *distance = 0;
+        }
+        let visited = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| false);
+        let cheapest = maze
+            .terrain
+            .indices()
+            .map(|pos| maze.terrain[pos].cost())
+            .min()
+            .unwrap_or(Terrain::Plain.cost());
 
         Self {
             maze,
-            is_done: false,
+            is_done,
             path: Vec::new(),
-            goal,
+            goals,
+            heuristic: true,
+            cheapest,
             unvisited,
             distances,
+            visited,
+        }
+    }
+
+    /// Solves using Dijkstra's algorithm, which explores outwards from the
+    /// start without heading towards the goal.
+    pub fn dijkstra(maze: Maze, start: Vector, goal: Vector) -> Self {
+        Self {
+            heuristic: false,
+            ..Self::with_endpoints(maze, start, goal)
         }
     }
 
     pub fn step(&mut self) {
+        if self.is_done {
+            return;
+        }
+
+        // Skip cells that were already reached by a shorter route.
+        let next = loop {
+            match self.unvisited.pop() {
+                Some(unvisited) if self.visited[unvisited.position] => continue,
+                next => break next,
+            }
+        };
         if let Some(Unvisited {
             score: _,
             distance,
             position,
-        }) = self.unvisited.pop()
+        }) = next
         {
-            if position == self.goal {
-                self.fill_path();
+            self.visited[position] = true;
+
+            if self.goals.contains(&position) {
+                self.fill_path(position);
                 self.is_done = true;
                 return;
             }
@@ -86,29 +260,48 @@ impl Solver {
                     continue;
                 }
 
-                let new_distance = distance + dir.length();
+                let new_distance = distance + self.maze.terrain[neighbor].cost();
                 if new_distance < self.distances[neighbor] {
                     self.distances[neighbor] = new_distance;
+                    let estimate = if self.heuristic {
+                        self.estimate(neighbor)
+                    } else {
+                        0
+                    };
                     self.unvisited.push(Unvisited {
-                        score: new_distance + (neighbor - self.goal).length(),
+                        score: new_distance + estimate,
                         distance: new_distance,
                         position: neighbor,
                     });
                 }
             }
+        } else {
+            // The goal is unreachable.
+            self.is_done = true;
         }
 
-        for pos in self.maze.cells.indices() {
-            self.maze.cells[pos].background = CLEAR_COLOR;
-        }
-        self.maze.cells[self.goal].background = RED;
-        for unvisited in &self.unvisited {
-            self.maze.cells[unvisited.position].background = GREEN;
-        }
+        let frontier = self.frontier();
+        paint(&mut self.maze, &self.visited, frontier, &self.goals);
     }
 
-    fn fill_path(&mut self) {
-        let mut position = self.goal;
+    /// The cost of reaching the nearest goal from `position`, were there no
+    /// walls and the whole way as cheap as the cheapest terrain.
+    ///
+    /// This never overestimates, so the first route found is a cheapest one.
+    /// It also drops by no more than the cost of any step, so no cell needs
+    /// exploring twice.
+    fn estimate(&self, position: Vector) -> i32 {
+        let steps = self
+            .goals
+            .iter()
+            .map(|&goal| (position - goal).distance())
+            .min()
+            .unwrap_or(0);
+        steps * self.cheapest
+    }
+
+    fn fill_path(&mut self, goal: Vector) {
+        let mut position = goal;
         self.path.push(position);
 
         while self.distances[position] > 0 {
@@ -123,3 +316,549 @@ impl Solver {
         }
     }
 }
+
+impl MazeSolver for Solver {
+    fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        Solver::step(self);
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn path(&self) -> &[Vector] {
+        &self.path
+    }
+
+    fn frontier(&self) -> Vec<Vector> {
+        self.unvisited
+            .iter()
+            .map(|unvisited| unvisited.position)
+            .filter(|&pos| !self.visited[pos])
+            .collect()
+    }
+
+    fn visited(&self) -> &HexGrid<bool> {
+        &self.visited
+    }
+}
+
+/// Colors the maze to show a solver's progress, with the explored cells in
+/// grey, the frontier in green and the goal in red.
+fn paint(
+    maze: &mut Maze,
+    visited: &HexGrid<bool>,
+    frontier: impl IntoIterator<Item = Vector>,
+    goals: &[Vector],
+) {
+    maze.clear_backgrounds();
+    for pos in maze.cells.indices() {
+        if visited[pos] {
+            maze.cells[pos].background = LIGHT_GREY;
+        }
+    }
+    for pos in frontier {
+        maze.cells[pos].background = GREEN;
+    }
+    for &goal in goals {
+        if maze.cells.contains(goal) {
+            maze.cells[goal].background = RED;
+        }
+    }
+}
+
+/// Follows `parents` from the goal back to the start, whose parent is
+/// itself. Empty if the goal has no parent.
+fn trace_path(parents: &HexGrid<Option<Vector>>, goal: Vector) -> Vec<Vector> {
+    let mut path = Vec::new();
+    let mut position = goal;
+    while let Some(parent) = parents.get(position).copied().flatten() {
+        path.push(position);
+        if parent == position {
+            break;
+        }
+        position = parent;
+    }
+    path
+}
+
+/// Moves along a walk through the maze, cutting out any loops so that
+/// `route` is always a simple route from the start to the current cell.
+fn walk_to(route: &mut Vec<Vector>, next: Vector) {
+    match route.iter().position(|&pos| pos == next) {
+        Some(index) => route.truncate(index + 1),
+        None => route.push(next),
+    }
+}
+
+/// The number of moves from `start` to every cell of the maze, or `None` for
+/// cells that cannot be reached from it.
+pub fn distances(maze: &Maze, start: Vector) -> HexGrid<Option<u32>> {
+    let mut distances = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| None);
+    if !maze.cells.contains(start) {
+        return distances;
+    }
+
+    distances[start] = Some(0);
+    let mut queue = VecDeque::from([start]);
+    while let Some(position) = queue.pop_front() {
+        let distance = distances[position].unwrap() + 1;
+        for dir in Vector::DIRECTIONS {
+            let neighbor = position + dir;
+            if !maze.wall_between(position, neighbor) && distances[neighbor].is_none() {
+                distances[neighbor] = Some(distance);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+    distances
+}
+
+/// The cheapest [`Terrain::cost`] of getting from `start` to every cell of
+/// the maze, as [`Solver`] measures routes, or `None` for cells that cannot be
+/// reached from it. Without terrain, this is twice the number of moves.
+pub fn costs(maze: &Maze, start: Vector) -> HexGrid<Option<u32>> {
+    let mut costs = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| None);
+    if !maze.cells.contains(start) {
+        return costs;
+    }
+
+    costs[start] = Some(0);
+    let mut unvisited = BinaryHeap::from([Reverse((0, start))]);
+    while let Some(Reverse((cost, position))) = unvisited.pop() {
+        if costs[position].is_some_and(|best| best < cost) {
+            continue;
+        }
+        for dir in Vector::DIRECTIONS {
+            let neighbor = position + dir;
+            if maze.wall_between(position, neighbor) {
+                continue;
+            }
+            let cost = cost + maze.terrain[neighbor].cost() as u32;
+            if costs[neighbor].is_none_or(|best| cost < best) {
+                costs[neighbor] = Some(cost);
+                unvisited.push(Reverse((cost, neighbor)));
+            }
+        }
+    }
+    costs
+}
+
+/// The total [`Terrain::cost`] of a path, as returned by
+/// [`MazeSolver::path`], which is every cell stepped into after the start.
+pub fn cost(maze: &Maze, path: &[Vector]) -> i32 {
+    let stepped = path.len().saturating_sub(1);
+    path[..stepped]
+        .iter()
+        .map(|&pos| maze.terrain[pos].cost())
+        .sum()
+}
+
+/// The cell farthest from `start` by moves through the maze, and how many
+/// moves away it is, or `None` if `start` is outside the maze.
+pub fn farthest(maze: &Maze, start: Vector) -> Option<(Vector, u32)> {
+    let distances = distances(maze, start);
+    maze.cells
+        .indices()
+        .filter_map(|pos| Some((Vector::from(pos), distances[pos]?)))
+        .max_by_key(|&(_, distance)| distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use rand::SeedableRng;
+
+    use crate::{
+        generator::{self, BraidGenerator, MazeGenerator, SeededRng},
+        hex::{Position, Vector},
+        maze::{
+            fixtures::{branch, BOTTOM},
+            Maze,
+        },
+        solver::{
+            best_order, cost, costs, distances, Algorithm, DfsSolver, MazeSolver, RouteSolver,
+            Solver,
+        },
+        terrain::{self, Terrain},
+    };
+
+    fn generate(cols: u16, rows: u16, seed: u64, braid: f64) -> Maze {
+        let generator = generator::Algorithm::Dfs.generator(cols, rows, seed);
+        BraidGenerator::with_seed(generator, braid, seed)
+            .finish()
+            .clone()
+    }
+
+    /// Runs the solver to the end, checking that its route is made of
+    /// passages from the goal back to the start.
+    fn solve(algorithm: Algorithm, maze: &Maze, start: Vector, goal: Vector) -> Vec<Vector> {
+        let mut solver = algorithm.solver(maze.clone(), start, goal);
+        let mut steps = 0;
+        while !solver.is_done() {
+            solver.step();
+            steps += 1;
+            assert!(steps < 100_000, "{algorithm} didn't finish");
+        }
+
+        let path = solver.path().to_vec();
+        if let (Some(&first), Some(&last)) = (path.first(), path.last()) {
+            assert_eq!((first, last), (goal, start), "{algorithm}");
+        }
+        for pair in path.windows(2) {
+            assert!(!maze.wall_between(pair[0], pair[1]), "{algorithm}");
+        }
+        path
+    }
+
+    #[test]
+    fn test_solvers_perfect_maze() {
+        let maze = generate(12, 8, 3, 0.0);
+        let goal = Vector::from(Position { col: 11, row: 7 });
+        let moves = distances(&maze, Vector::ZERO)[goal].unwrap() as usize;
+        for algorithm in Algorithm::ALL {
+            // There is only one route.
+            assert_eq!(
+                solve(algorithm, &maze, Vector::ZERO, goal).len(),
+                moves + 1,
+                "{algorithm}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_solvers_braided_maze() {
+        let maze = generate(12, 8, 3, 1.0);
+        let start = Vector::from(Position { col: 2, row: 3 });
+        let goal = Vector::from(Position { col: 9, row: 5 });
+        let moves = distances(&maze, start)[goal].unwrap() as usize;
+        for algorithm in Algorithm::ALL {
+            let path = solve(algorithm, &maze, start, goal);
+            match algorithm {
+                Algorithm::AStar
+                | Algorithm::Dijkstra
+                | Algorithm::Bfs
+                | Algorithm::DeadEndFilling => {
+                    assert_eq!(path.len(), moves + 1, "{algorithm}")
+                }
+                // Following a wall can get stuck going round an island.
+                Algorithm::LeftHand | Algorithm::RightHand => {}
+                // A route is found, though maybe not a shortest one.
+                Algorithm::Dfs | Algorithm::Tremaux => {
+                    assert!(path.len() > moves, "{algorithm}")
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_dfs_frontier() {
+        let dead_end = Vector::SOUTH + Vector::SOUTH_EAST;
+        let mut solver = DfsSolver::new(branch(), Vector::ZERO, BOTTOM);
+        assert_eq!(solver.frontier(), vec![Vector::SOUTH]);
+        solver.step();
+        assert_eq!(solver.frontier(), vec![dead_end, BOTTOM]);
+        solver.step();
+        // About to backtrack out of the dead end.
+        assert_eq!(solver.frontier(), Vec::new());
+        solver.step();
+        assert_eq!(solver.frontier(), vec![BOTTOM]);
+    }
+
+    #[test]
+    fn test_solvers_outside() {
+        let maze = generate(6, 4, 3, 0.5);
+        let goal = Vector::from(Position { col: 5, row: 3 });
+        for algorithm in Algorithm::ALL {
+            for (maze, start) in [(&Maze::empty(), Vector::ZERO), (&maze, Vector::NORTH)] {
+                let mut solver = algorithm.solver(maze.clone(), start, goal);
+                assert!(solver.is_done(), "{algorithm}");
+                solver.step();
+                assert!(solver.path().is_empty(), "{algorithm}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_solvers_unreachable() {
+        let mut maze = generate(6, 4, 3, 0.5);
+        let goal = Vector::from(Position { col: 3, row: 2 });
+        for dir in Vector::DIRECTIONS {
+            maze.set_wall_between(goal, goal + dir, true);
+        }
+        for algorithm in Algorithm::ALL {
+            assert!(solve(algorithm, &maze, Vector::ZERO, goal).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_solver_progress() {
+        let maze = generate(12, 8, 3, 0.5);
+        let goal = Vector::from(Position { col: 11, row: 7 });
+        for algorithm in Algorithm::ALL {
+            let mut solver = algorithm.solver(maze.clone(), Vector::ZERO, goal);
+            for _ in 0..5 {
+                solver.step();
+            }
+            assert!(!solver.frontier().is_empty(), "{algorithm}");
+            for pos in solver.frontier() {
+                assert!(maze.cells.contains(pos));
+            }
+            let visited = solver.visited();
+            assert!(visited.indices().any(|pos| visited[pos]), "{algorithm}");
+            assert!(solver.path().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_distances() {
+        let mut maze = Maze::with_size(2, 2);
+        maze.set_wall_between(Vector::ZERO, Vector::SOUTH, false);
+        maze.set_wall_between(Vector::SOUTH, Vector::SOUTH_EAST, false);
+
+        let distances = distances(&maze, Vector::ZERO);
+        assert_eq!(distances[Vector::ZERO], Some(0));
+        assert_eq!(distances[Vector::SOUTH], Some(1));
+        assert_eq!(distances[Vector::SOUTH_EAST], Some(2));
+        assert_eq!(distances[Position { col: 1, row: 1 }], None);
+
+        let outside = super::distances(&maze, Vector::NORTH);
+        assert!(outside.indices().all(|pos| outside[pos].is_none()));
+    }
+
+    #[test]
+    fn test_nearest_goal() {
+        let maze = generate(12, 8, 3, 0.5);
+        let distances = distances(&maze, Vector::ZERO);
+        let goals: Vec<_> = [(11, 7), (4, 6), (10, 0)]
+            .map(|(col, row)| Vector::from(Position { col, row }))
+            .into();
+        let nearest = *goals.iter().min_by_key(|&&goal| distances[goal]).unwrap();
+
+        let mut solver = Solver::with_goals(maze, Vector::ZERO, goals);
+        while !solver.is_done {
+            solver.step();
+        }
+        assert_eq!(solver.path.first(), Some(&nearest));
+        assert_eq!(solver.path.len() as u32, distances[nearest].unwrap() + 1);
+    }
+
+    #[test]
+    fn test_route() {
+        let maze = generate(12, 8, 3, 0.5);
+        let start = Vector::from(Position { col: 2, row: 3 });
+        let waypoints: Vec<_> = [(11, 0), (0, 7)]
+            .map(|(col, row)| Vector::from(Position { col, row }))
+            .into();
+        let goal = Vector::from(Position { col: 9, row: 5 });
+
+        let mut solver = RouteSolver::new(maze.clone(), start, waypoints.clone(), vec![goal]);
+        while !solver.is_done() {
+            solver.step();
+        }
+        let path = solver.path();
+        assert_eq!((path.first(), path.last()), (Some(&goal), Some(&start)));
+        for pair in path.windows(2) {
+            assert!(!maze.wall_between(pair[0], pair[1]));
+        }
+
+        // Each leg is a shortest route, visiting the waypoints in order.
+        let stops = [start, waypoints[0], waypoints[1], goal];
+        let moves: u32 = stops
+            .windows(2)
+            .map(|leg| distances(&maze, leg[0])[leg[1]].unwrap())
+            .sum();
+        assert_eq!(path.len() as u32, moves + 1);
+        let first = path.iter().rposition(|&pos| pos == waypoints[0]).unwrap();
+        assert!(path[..first].contains(&waypoints[1]));
+    }
+
+    #[test]
+    fn test_route_unreachable() {
+        let mut maze = generate(6, 4, 3, 0.5);
+        let waypoint = Vector::from(Position { col: 3, row: 2 });
+        for dir in Vector::DIRECTIONS {
+            maze.set_wall_between(waypoint, waypoint + dir, true);
+        }
+        let goal = Vector::from(Position { col: 5, row: 3 });
+
+        let mut solver = RouteSolver::new(maze, Vector::ZERO, vec![waypoint], vec![goal]);
+        while !solver.is_done() {
+            solver.step();
+        }
+        assert!(solver.path().is_empty());
+    }
+
+    #[test]
+    fn test_best_order() {
+        // A single passage down the first column.
+        let mut maze = Maze::with_size(1, 6);
+        for row in 0..5 {
+            let pos = Vector::from(Position { col: 0, row });
+            maze.set_wall_between(pos, pos + Vector::SOUTH, false);
+        }
+        let cell = |row| Vector::from(Position { col: 0, row });
+
+        let waypoints = [cell(4), cell(1), cell(2)];
+        assert_eq!(
+            best_order(&maze, cell(0), &waypoints, &[cell(5)]),
+            [cell(1), cell(2), cell(4)],
+        );
+        assert_eq!(
+            best_order(&maze, cell(3), &waypoints, &[]),
+            [cell(4), cell(2), cell(1)],
+        );
+        // The nearest goal counts, wherever it is.
+        assert_eq!(
+            best_order(&maze, cell(3), &waypoints, &[cell(0), cell(5)]),
+            [cell(4), cell(2), cell(1)],
+        );
+        assert_eq!(
+            best_order(&maze, cell(3), &[cell(1), cell(4)], &[cell(5)]),
+            [cell(1), cell(4)],
+        );
+
+        // Crossing water twice costs more than the extra moves.
+        assert_eq!(
+            best_order(&maze, cell(2), &[cell(0), cell(5)], &[]),
+            [cell(0), cell(5)],
+        );
+        maze.terrain[cell(1)] = Terrain::Water;
+        assert_eq!(
+            best_order(&maze, cell(2), &[cell(0), cell(5)], &[]),
+            [cell(5), cell(0)],
+        );
+    }
+
+    /// Runs the solver to the end, returning its path and how many cells it
+    /// explored.
+    fn explore(mut solver: Solver) -> (Vec<Vector>, usize) {
+        while !solver.is_done {
+            solver.step();
+        }
+        let explored = solver.visited().indices();
+        let explored = explored.filter(|&pos| solver.visited()[pos]).count();
+        (solver.path, explored)
+    }
+
+    #[test]
+    fn test_terrain() {
+        // No walls, with water down the middle but for a road across it.
+        let mut maze = Maze::with_size(5, 5);
+        for pos in maze.cells.indices() {
+            for dir in [Vector::NORTH_EAST, Vector::SOUTH_EAST, Vector::SOUTH] {
+                if maze.cells.contains(Vector::from(pos) + dir) {
+                    maze.set_wall_between(pos.into(), Vector::from(pos) + dir, false);
+                }
+            }
+        }
+        for row in 0..5 {
+            maze.terrain[Position { col: 2, row }] = if row == 2 {
+                Terrain::Road
+            } else {
+                Terrain::Water
+            };
+        }
+        let start = Vector::ZERO;
+        let goal = Vector::from(Position { col: 4, row: 0 });
+
+        let (path, _) = explore(Solver::with_endpoints(maze.clone(), start, goal));
+        assert!(path.contains(&Position { col: 2, row: 2 }.into()));
+        let (dijkstra, _) = explore(Solver::dijkstra(maze.clone(), start, goal));
+        assert_eq!(cost(&maze, &dijkstra), cost(&maze, &path));
+
+        // Counting moves alone goes straight through the water.
+        let bfs = solve(Algorithm::Bfs, &maze, start, goal);
+        assert!(bfs.len() < path.len());
+        assert!(cost(&maze, &bfs) > cost(&maze, &path));
+    }
+
+    #[test]
+    fn test_costs() {
+        let maze = generate(12, 8, 3, 0.5);
+        let (costs, distances) = (costs(&maze, Vector::ZERO), distances(&maze, Vector::ZERO));
+        for pos in maze.cells.indices() {
+            assert_eq!(costs[pos], distances[pos].map(|moves| moves * 2));
+        }
+        let outside = super::costs(&maze, Vector::NORTH);
+        assert!(outside.indices().all(|pos| outside[pos].is_none()));
+
+        for seed in 0..5 {
+            let mut maze = generate(8, 6, seed, 0.5);
+            terrain::scatter(&mut maze, &mut SeededRng::seed_from_u64(seed));
+            let costs = super::costs(&maze, Vector::ZERO);
+            for pos in maze.cells.indices().map(Vector::from) {
+                let (path, _) = explore(Solver::dijkstra(maze.clone(), Vector::ZERO, pos));
+                assert_eq!(costs[pos], Some(cost(&maze, &path) as u32), "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_terrain_astar_matches_dijkstra() {
+        for seed in 0..20 {
+            let mut maze = generate(16, 10, seed, 0.5);
+            terrain::scatter(&mut maze, &mut SeededRng::seed_from_u64(seed));
+            let goal = Vector::from(Position { col: 15, row: 9 });
+
+            let (astar, astar_explored) =
+                explore(Solver::with_endpoints(maze.clone(), Vector::ZERO, goal));
+            let (dijkstra, dijkstra_explored) =
+                explore(Solver::dijkstra(maze.clone(), Vector::ZERO, goal));
+            assert_eq!(cost(&maze, &astar), cost(&maze, &dijkstra), "seed {seed}");
+            assert!(astar_explored <= dijkstra_explored, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_estimate_is_admissible() {
+        for seed in 0..5 {
+            let mut maze = generate(8, 6, seed, 0.5);
+            terrain::scatter(&mut maze, &mut SeededRng::seed_from_u64(seed));
+            let goal = Vector::from(Position { col: 7, row: 5 });
+            let solver = Solver::with_endpoints(maze.clone(), Vector::ZERO, goal);
+            assert_eq!(solver.estimate(goal), 0);
+
+            for pos in maze.cells.indices().map(Vector::from) {
+                // Never more than the cheapest route.
+                let (path, _) = explore(Solver::dijkstra(maze.clone(), pos, goal));
+                assert!(solver.estimate(pos) <= cost(&maze, &path), "seed {seed}");
+
+                // Never dropping by more than a step costs.
+                for neighbor in maze.open_neighbors(pos) {
+                    let step = maze.terrain[neighbor].cost();
+                    assert!(solver.estimate(pos) <= step + solver.estimate(neighbor));
+                }
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_astar_finds_shortest_route(
+            cols in 1..24u16,
+            rows in 1..16u16,
+            seed: u64,
+            braid in 0.0..=1.0,
+            goal: (u16, u16),
+        ) {
+            let maze = generate(cols, rows, seed, braid);
+            let goal = Vector::from(Position {
+                col: (goal.0 % cols) as i16,
+                row: (goal.1 % rows) as i16,
+            });
+            let moves = distances(&maze, Vector::ZERO)[goal].unwrap();
+
+            let (path, _) = explore(Solver::with_endpoints(maze.clone(), Vector::ZERO, goal));
+            prop_assert_eq!(path.len() as u32, moves + 1);
+            prop_assert_eq!(path.first(), Some(&goal));
+            for pair in path.windows(2) {
+                prop_assert!(!maze.wall_between(pair[0], pair[1]));
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+
+use crate::{
+    grid::HexGrid,
+    hex::Vector,
+    maze::Maze,
+    solver::{paint, trace_path, MazeSolver},
+};
+
+/// Solves a maze with a breadth-first search, exploring the cells in order
+/// of how many moves they are from the start.
+///
+/// Always finds a shortest route.
+pub struct BfsSolver {
+    maze: Maze,
+    goal: Vector,
+    is_done: bool,
+    path: Vec<Vector>,
+    queue: VecDeque<Vector>,
+    /// The cell each cell was reached from. The start is its own parent.
+    parents: HexGrid<Option<Vector>>,
+    visited: HexGrid<bool>,
+}
+
+impl BfsSolver {
+    pub fn new(maze: Maze, start: Vector, goal: Vector) -> Self {
+        let is_done = !maze.cells.contains(start);
+        let mut parents = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| None);
+        if let Some(parent) = parents.get_mut(start) {
+            *parent = Some(start);
+        }
+        let visited = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| false);
+        Self {
+            maze,
+            goal,
+            is_done,
+            path: Vec::new(),
+            queue: VecDeque::from([start]),
+            parents,
+            visited,
+        }
+    }
+}
+
+impl MazeSolver for BfsSolver {
+    fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.is_done {
+            return;
+        }
+
+        let Some(position) = self.queue.pop_front() else {
+            // The goal is unreachable.
+            self.is_done = true;
+            return;
+        };
+        self.visited[position] = true;
+        if position == self.goal {
+            self.path = trace_path(&self.parents, self.goal);
+            self.is_done = true;
+            return;
+        }
+
+        for neighbor in self.maze.open_neighbors(position) {
+            if self.parents[neighbor].is_none() {
+                self.parents[neighbor] = Some(position);
+                self.queue.push_back(neighbor);
+            }
+        }
+
+        let frontier = self.frontier();
+        paint(&mut self.maze, &self.visited, frontier, &[self.goal]);
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn path(&self) -> &[Vector] {
+        &self.path
+    }
+
+    fn frontier(&self) -> Vec<Vector> {
+        self.queue.iter().copied().collect()
+    }
+
+    fn visited(&self) -> &HexGrid<bool> {
+        &self.visited
+    }
+}
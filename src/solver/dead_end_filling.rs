@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+
+use crate::{
+    grid::HexGrid,
+    hex::Vector,
+    maze::Maze,
+    solver::{paint, trace_path, MazeSolver},
+};
+
+/// Solves a maze by filling in its dead ends, one cell per step, until only
+/// the cells on routes from the start to the goal are left.
+///
+/// Filling a dead end can leave its neighbor as a new dead end, which is
+/// filled in turn. In a perfect maze, the cells left over are exactly the
+/// route. In a maze with loops, the shortest route through them is taken.
+pub struct DeadEndFiller {
+    maze: Maze,
+    start: Vector,
+    goal: Vector,
+    is_done: bool,
+    path: Vec<Vector>,
+    dead_ends: VecDeque<Vector>,
+    filled: HexGrid<bool>,
+}
+
+impl DeadEndFiller {
+    pub fn new(maze: Maze, start: Vector, goal: Vector) -> Self {
+        let is_done = !maze.cells.contains(start);
+        let filled = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| false);
+        let mut filler = Self {
+            maze,
+            start,
+            goal,
+            is_done,
+            path: Vec::new(),
+            dead_ends: VecDeque::new(),
+            filled,
+        };
+        filler.dead_ends = filler
+            .maze
+            .cells
+            .indices()
+            .map(Vector::from)
+            .filter(|&pos| filler.is_dead_end(pos))
+            .collect();
+        filler
+    }
+
+    fn open_neighbors(&self, pos: Vector) -> impl Iterator<Item = Vector> + '_ {
+        self.maze
+            .open_neighbors(pos)
+            .filter(|&neighbor| !self.filled[neighbor])
+    }
+
+    fn is_dead_end(&self, pos: Vector) -> bool {
+        pos != self.start && pos != self.goal && self.open_neighbors(pos).count() == 1
+    }
+
+    /// The shortest route through the cells that weren't filled.
+    fn find_path(&self) -> Vec<Vector> {
+        if !self.maze.cells.contains(self.start) {
+            return Vec::new();
+        }
+
+        let mut parents =
+            HexGrid::new_with(self.maze.cells.cols(), self.maze.cells.rows(), |_| None);
+        parents[self.start] = Some(self.start);
+        let mut queue = VecDeque::from([self.start]);
+        while let Some(position) = queue.pop_front() {
+            for neighbor in self.open_neighbors(position) {
+                if parents[neighbor].is_none() {
+                    parents[neighbor] = Some(position);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        trace_path(&parents, self.goal)
+    }
+}
+
+impl MazeSolver for DeadEndFiller {
+    fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.is_done {
+            return;
+        }
+
+        let Some(position) = self.dead_ends.pop_front() else {
+            self.path = self.find_path();
+            self.is_done = true;
+            return;
+        };
+        self.filled[position] = true;
+        let neighbors: Vec<_> = self.open_neighbors(position).collect();
+        for neighbor in neighbors {
+            if self.is_dead_end(neighbor) {
+                self.dead_ends.push_back(neighbor);
+            }
+        }
+
+        let frontier = self.frontier();
+        paint(&mut self.maze, &self.filled, frontier, &[self.goal]);
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn path(&self) -> &[Vector] {
+        &self.path
+    }
+
+    fn frontier(&self) -> Vec<Vector> {
+        self.dead_ends.iter().copied().collect()
+    }
+
+    /// The cells that have been filled in.
+    fn visited(&self) -> &HexGrid<bool> {
+        &self.filled
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    grid::HexGrid,
+    hex::Vector,
+    maze::Maze,
+    solver::{paint, MazeSolver},
+};
+
+/// Solves a maze with a depth-first search, following each passage as far
+/// as it goes before backtracking to try the next.
+///
+/// Finds a route, though not always the shortest one if the maze has loops.
+pub struct DfsSolver {
+    maze: Maze,
+    goal: Vector,
+    is_done: bool,
+    path: Vec<Vector>,
+    /// The route from the start to the cell being explored.
+    stack: Vec<Vector>,
+    visited: HexGrid<bool>,
+}
+
+impl DfsSolver {
+    pub fn new(maze: Maze, start: Vector, goal: Vector) -> Self {
+        let is_done = !maze.cells.contains(start);
+        let mut visited = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| false);
+        if let Some(visited) = visited.get_mut(start) {
+            *visited = true;
+        }
+        Self {
+            maze,
+            goal,
+            is_done,
+            path: Vec::new(),
+            stack: vec![start],
+            visited,
+        }
+    }
+}
+
+impl MazeSolver for DfsSolver {
+    fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.is_done {
+            return;
+        }
+
+        let Some(&position) = self.stack.last() else {
+            // The goal is unreachable.
+            self.is_done = true;
+            return;
+        };
+        if position == self.goal {
+            self.path = self.stack.iter().rev().copied().collect();
+            self.is_done = true;
+            return;
+        }
+
+        let next = self
+            .maze
+            .open_neighbors(position)
+            .find(|&neighbor| !self.visited[neighbor]);
+        if let Some(next) = next {
+            self.visited[next] = true;
+            self.stack.push(next);
+        } else {
+            self.stack.pop();
+        }
+
+        let frontier = self.frontier();
+        paint(&mut self.maze, &self.visited, frontier, &[self.goal]);
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn path(&self) -> &[Vector] {
+        &self.path
+    }
+
+    /// The unexplored neighbors of the cell being explored, which are empty
+    /// when it is about to backtrack.
+    fn frontier(&self) -> Vec<Vector> {
+        let Some(&position) = self.stack.last() else {
+            return Vec::new();
+        };
+        self.maze
+            .open_neighbors(position)
+            .filter(|&neighbor| !self.visited[neighbor])
+            .collect()
+    }
+
+    fn visited(&self) -> &HexGrid<bool> {
+        &self.visited
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    grid::HexGrid,
+    hex::Vector,
+    maze::Maze,
+    solver::{costs, MazeSolver, Solver},
+    terminal::{BLUE, MAGENTA},
+};
+
+/// The most waypoints [`best_order`] will try every order of.
+pub const MAX_BEST_ORDER: usize = 8;
+
+/// Solves a route from the start through each waypoint in turn, then on to
+/// whichever goal is nearest, finding each leg with A*.
+///
+/// The path is the legs joined together, so it can pass through a cell more
+/// than once.
+pub struct RouteSolver {
+    maze: Maze,
+    is_done: bool,
+    path: Vec<Vector>,
+    /// The waypoints not reached yet, with the next one last.
+    waypoints: Vec<Vector>,
+    goals: Vec<Vector>,
+    leg: Solver,
+    /// The legs found so far, from the start.
+    route: Vec<Vector>,
+    visited: HexGrid<bool>,
+}
+
+impl RouteSolver {
+    pub fn new(maze: Maze, start: Vector, mut waypoints: Vec<Vector>, goals: Vec<Vector>) -> Self {
+        waypoints.reverse();
+        let visited = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| false);
+        let leg = Self::leg(&maze, start, &waypoints, &goals);
+        Self {
+            maze,
+            is_done: false,
+            path: Vec::new(),
+            waypoints,
+            goals,
+            leg,
+            route: Vec::new(),
+            visited,
+        }
+    }
+
+    /// Starts the leg from `start` to the next waypoint, or to the nearest
+    /// goal once there are no more.
+    fn leg(maze: &Maze, start: Vector, waypoints: &[Vector], goals: &[Vector]) -> Solver {
+        match waypoints.last() {
+            Some(&waypoint) => Solver::with_endpoints(maze.clone(), start, waypoint),
+            None => Solver::with_goals(maze.clone(), start, goals.to_vec()),
+        }
+    }
+
+    fn paint(&mut self) {
+        self.maze.copy_from(&self.leg.maze);
+        for &pos in &self.route {
+            self.maze.cells[pos].background = BLUE;
+        }
+        for &pos in &self.waypoints {
+            if self.maze.cells.contains(pos) {
+                self.maze.cells[pos].background = MAGENTA;
+            }
+        }
+    }
+}
+
+impl MazeSolver for RouteSolver {
+    fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.is_done {
+            return;
+        }
+
+        self.leg.step();
+        for pos in self.visited.indices() {
+            self.visited[pos] |= self.leg.visited[pos];
+        }
+
+        if self.leg.is_done {
+            if self.leg.path.is_empty() {
+                // The next waypoint or goal is unreachable.
+                self.route.clear();
+                self.is_done = true;
+                return;
+            }
+
+            // Each leg starts where the last one ended.
+            let skip = usize::from(!self.route.is_empty());
+            self.route
+                .extend(self.leg.path.iter().rev().skip(skip).copied());
+            if self.waypoints.pop().is_some() {
+                let start = *self.route.last().unwrap();
+                self.leg = Self::leg(&self.maze, start, &self.waypoints, &self.goals);
+            } else {
+                self.path = self.route.iter().rev().copied().collect();
+                self.is_done = true;
+                return;
+            }
+        }
+
+        self.paint();
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn path(&self) -> &[Vector] {
+        &self.path
+    }
+
+    fn frontier(&self) -> Vec<Vector> {
+        self.leg.frontier()
+    }
+
+    /// The cells explored by any of the legs.
+    fn visited(&self) -> &HexGrid<bool> {
+        &self.visited
+    }
+}
+
+/// Orders the waypoints so that the route from `start` through all of them,
+/// then on to the nearest goal, is as cheap as it can be.
+///
+/// Every order is tried, so this leaves more than [`MAX_BEST_ORDER`]
+/// waypoints as they are, as it does if no order can be completed.
+pub fn best_order(
+    maze: &Maze,
+    start: Vector,
+    waypoints: &[Vector],
+    goals: &[Vector],
+) -> Vec<Vector> {
+    if waypoints.len() < 2 || waypoints.len() > MAX_BEST_ORDER {
+        return waypoints.to_vec();
+    }
+
+    // The start is point 0, and each waypoint is the point after its index.
+    let points: Vec<_> = [start]
+        .into_iter()
+        .chain(waypoints.iter().copied())
+        .collect();
+    let mut search = Search {
+        legs: Vec::new(),
+        finish: Vec::new(),
+        best: None,
+    };
+    for &point in &points {
+        let costs = costs(maze, point);
+        search.legs.push(
+            waypoints
+                .iter()
+                .map(|&pos| costs.get(pos).copied().flatten())
+                .collect(),
+        );
+        let finish = goals
+            .iter()
+            .filter_map(|&goal| costs.get(goal).copied().flatten())
+            .min();
+        search
+            .finish
+            .push(if goals.is_empty() { Some(0) } else { finish });
+    }
+
+    search.visit(0, &mut Vec::new(), 0);
+    match search.best {
+        Some((_, order)) => order.into_iter().map(|index| waypoints[index]).collect(),
+        None => waypoints.to_vec(),
+    }
+}
+
+/// A search through the orders of the waypoints for the cheapest route.
+struct Search {
+    /// The cost of getting from each point to each waypoint.
+    legs: Vec<Vec<Option<u32>>>,
+    /// The cost of getting from each point to the nearest goal.
+    finish: Vec<Option<u32>>,
+    /// The cost of the cheapest route so far, and its order.
+    best: Option<(u32, Vec<usize>)>,
+}
+
+impl Search {
+    fn visit(&mut self, from: usize, order: &mut Vec<usize>, cost: u32) {
+        if self.best.as_ref().is_some_and(|&(best, _)| cost >= best) {
+            return;
+        }
+        if order.len() == self.legs[from].len() {
+            if let Some(finish) = self.finish[from] {
+                let total = cost + finish;
+                if self.best.as_ref().is_none_or(|&(best, _)| total < best) {
+                    self.best = Some((total, order.clone()));
+                }
+            }
+            return;
+        }
+
+        for next in 0..self.legs[from].len() {
+            let Some(leg) = self.legs[from][next] else {
+                continue;
+            };
+            if order.contains(&next) {
+                continue;
+            }
+            order.push(next);
+            self.visit(next + 1, order, cost + leg);
+            order.pop();
+        }
+    }
+}
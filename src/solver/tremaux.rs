@@ -0,0 +1,126 @@
+use crate::{
+    grid::HexGrid,
+    hex::Vector,
+    maze::Maze,
+    solver::{paint, walk_to, MazeSolver},
+};
+
+/// Solves a maze with Trémaux's algorithm, walking through it and marking
+/// each passage every time it is used.
+///
+/// On reaching a cell that was already visited through a new passage, it
+/// turns back. Otherwise it takes the least marked passage, never one that
+/// has been used twice. Unlike following a wall, this always reaches the
+/// goal, even in a maze with loops, and the passages marked once make up a
+/// route.
+pub struct TremauxSolver {
+    maze: Maze,
+    goal: Vector,
+    is_done: bool,
+    path: Vec<Vector>,
+    position: Vector,
+    /// The direction back along the passage just used, as an index into
+    /// [`Vector::DIRECTIONS`].
+    came_from: Option<usize>,
+    turn_back: bool,
+    /// The number of times each passage has been used, by its direction
+    /// from the cell.
+    marks: HexGrid<[u8; 6]>,
+    /// The walk so far, with any loops cut out.
+    route: Vec<Vector>,
+    visited: HexGrid<bool>,
+}
+
+impl TremauxSolver {
+    pub fn new(maze: Maze, start: Vector, goal: Vector) -> Self {
+        let is_done = !maze.cells.contains(start);
+        let mut visited = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| false);
+        if let Some(visited) = visited.get_mut(start) {
+            *visited = true;
+        }
+        let marks = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| [0; 6]);
+        Self {
+            maze,
+            goal,
+            is_done,
+            path: Vec::new(),
+            position: start,
+            came_from: None,
+            turn_back: false,
+            marks,
+            route: vec![start],
+            visited,
+        }
+    }
+
+    fn pick_direction(&self) -> Option<usize> {
+        let position = self.position;
+        let marks = self.marks[position];
+        if self.turn_back {
+            return self.came_from;
+        }
+
+        let forward = (0..6)
+            .filter(|&dir| Some(dir) != self.came_from && marks[dir] < 2)
+            .filter(|&dir| {
+                !self
+                    .maze
+                    .wall_between(position, position + Vector::DIRECTIONS[dir])
+            })
+            .min_by_key(|&dir| marks[dir]);
+        forward.or(self.came_from.filter(|&dir| marks[dir] < 2))
+    }
+}
+
+impl MazeSolver for TremauxSolver {
+    fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.is_done {
+            return;
+        }
+
+        if self.position == self.goal {
+            self.path = self.route.iter().rev().copied().collect();
+            self.is_done = true;
+            return;
+        }
+
+        let Some(dir) = self.pick_direction() else {
+            // Every passage has been used twice, so the goal is unreachable.
+            self.is_done = true;
+            return;
+        };
+        let next = self.position + Vector::DIRECTIONS[dir];
+        let back = (dir + 3) % 6;
+        self.marks[self.position][dir] += 1;
+        self.marks[next][back] += 1;
+
+        self.turn_back = self.visited[next] && self.marks[next][back] == 1;
+        self.visited[next] = true;
+        self.came_from = Some(back);
+        self.position = next;
+        walk_to(&mut self.route, next);
+
+        let frontier = self.frontier();
+        paint(&mut self.maze, &self.visited, frontier, &[self.goal]);
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn path(&self) -> &[Vector] {
+        &self.path
+    }
+
+    fn frontier(&self) -> Vec<Vector> {
+        vec![self.position]
+    }
+
+    fn visited(&self) -> &HexGrid<bool> {
+        &self.visited
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    grid::HexGrid,
+    hex::Vector,
+    maze::Maze,
+    solver::{paint, walk_to, MazeSolver},
+};
+
+/// Which hand a [`WallFollower`] keeps on the wall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+impl Hand {
+    /// The turns to try at each cell, as steps clockwise through
+    /// [`Vector::DIRECTIONS`] from the direction being faced. Turning back is
+    /// always the last resort.
+    fn turns(self) -> [usize; 6] {
+        match self {
+            Self::Left => [4, 5, 0, 1, 2, 3],
+            Self::Right => [2, 1, 0, 5, 4, 3],
+        }
+    }
+}
+
+/// Solves a maze by walking through it with one hand on the wall, starting
+/// out facing south.
+///
+/// Always reaches the goal of a perfect maze, but can walk around a loop
+/// forever in a maze with loops. It gives up once it finds itself back in
+/// the same cell facing the same way.
+pub struct WallFollower {
+    maze: Maze,
+    goal: Vector,
+    hand: Hand,
+    is_done: bool,
+    path: Vec<Vector>,
+    position: Vector,
+    /// An index into [`Vector::DIRECTIONS`].
+    facing: usize,
+    /// The walk so far, with any loops cut out.
+    route: Vec<Vector>,
+    /// A bit for each direction the cell has been left in.
+    left_facing: HexGrid<u8>,
+    visited: HexGrid<bool>,
+}
+
+impl WallFollower {
+    pub fn new(maze: Maze, start: Vector, goal: Vector, hand: Hand) -> Self {
+        let is_done = !maze.cells.contains(start);
+        let mut visited = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| false);
+        if let Some(visited) = visited.get_mut(start) {
+            *visited = true;
+        }
+        let left_facing = HexGrid::new_with(maze.cells.cols(), maze.cells.rows(), |_| 0);
+        Self {
+            maze,
+            goal,
+            hand,
+            is_done,
+            path: Vec::new(),
+            position: start,
+            facing: 3,
+            route: vec![start],
+            left_facing,
+            visited,
+        }
+    }
+}
+
+impl MazeSolver for WallFollower {
+    fn maze(&self) -> &Maze {
+        &self.maze
+    }
+
+    fn step(&mut self) {
+        if self.is_done {
+            return;
+        }
+
+        if self.position == self.goal {
+            self.path = self.route.iter().rev().copied().collect();
+            self.is_done = true;
+            return;
+        }
+
+        let position = self.position;
+        let facing = self
+            .hand
+            .turns()
+            .into_iter()
+            .map(|turn| (self.facing + turn) % 6)
+            .find(|&dir| {
+                !self
+                    .maze
+                    .wall_between(position, position + Vector::DIRECTIONS[dir])
+            });
+        let Some(facing) = facing.filter(|dir| self.left_facing[position] & 1 << dir == 0) else {
+            // Either walled in, or going round in circles.
+            self.is_done = true;
+            return;
+        };
+        self.left_facing[position] |= 1 << facing;
+
+        self.facing = facing;
+        self.position = position + Vector::DIRECTIONS[facing];
+        self.visited[self.position] = true;
+        walk_to(&mut self.route, self.position);
+
+        let frontier = self.frontier();
+        paint(&mut self.maze, &self.visited, frontier, &[self.goal]);
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    fn path(&self) -> &[Vector] {
+        &self.path
+    }
+
+    fn frontier(&self) -> Vec<Vector> {
+        vec![self.position]
+    }
+
+    fn visited(&self) -> &HexGrid<bool> {
+        &self.visited
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hex::{Position, Vector},
+        maze::Maze,
+        solver::{Hand, MazeSolver, WallFollower},
+    };
+
+    fn solve(maze: &Maze, start: Vector, goal: Vector, hand: Hand) -> Vec<Vector> {
+        let mut solver = WallFollower::new(maze.clone(), start, goal, hand);
+        while !solver.is_done() {
+            solver.step();
+        }
+        solver.path().to_vec()
+    }
+
+    /// A ring of six cells around the middle one, which is walled off.
+    fn ring() -> (Maze, Vector) {
+        let middle = Vector::from(Position { col: 1, row: 1 });
+        let mut maze = Maze::with_size(3, 3);
+        for (i, dir) in Vector::DIRECTIONS.into_iter().enumerate() {
+            let next = Vector::DIRECTIONS[(i + 1) % 6];
+            maze.set_wall_between(middle + dir, middle + next, false);
+        }
+        (maze, middle)
+    }
+
+    #[test]
+    fn test_hands() {
+        let (maze, middle) = ring();
+        let start = middle + Vector::NORTH;
+        let goal = middle + Vector::SOUTH_EAST;
+
+        // The left hand goes clockwise round the ring, the right hand
+        // anticlockwise, and both find simple routes.
+        let left = solve(&maze, start, goal, Hand::Left);
+        let right = solve(&maze, start, goal, Hand::Right);
+        assert_eq!(left.len(), 3);
+        assert_eq!(right.len(), 5);
+        assert_eq!(left[1], middle + Vector::NORTH_EAST);
+        assert_eq!(right[1], middle + Vector::SOUTH);
+    }
+
+    #[test]
+    fn test_gives_up_on_island() {
+        // The goal is inside the ring, out of reach of the wall.
+        let (maze, middle) = ring();
+        let start = middle + Vector::NORTH;
+        assert!(solve(&maze, start, middle, Hand::Left).is_empty());
+        assert!(solve(&maze, start, middle, Hand::Right).is_empty());
+    }
+}
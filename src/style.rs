@@ -0,0 +1,339 @@
+use std::{fmt, str::FromStr};
+
+use crate::{
+    hex::{Position, Vector},
+    maze::Maze,
+    terminal::{Canvas, ScreenCell, CLEAR_COLOR},
+};
+
+/// The ways a maze can be drawn in the terminal, for selecting one at
+/// runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    /// `/`, `\` and `_`, four characters wide and two lines tall per cell.
+    #[default]
+    Ascii,
+    /// The same layout as [`Style::Ascii`], with Unicode line-drawing
+    /// characters.
+    Unicode,
+    /// Walls drawn as braille dots, which fits about five times as many
+    /// cells on the screen.
+    Braille,
+}
+
+/// The braille dots, indexed by `[y][x]` within a character.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+impl Style {
+    pub const ALL: [Self; 3] = [Self::Ascii, Self::Unicode, Self::Braille];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ascii => "ascii",
+            Self::Unicode => "unicode",
+            Self::Braille => "braille",
+        }
+    }
+
+    /// The style after this one in [`Style::ALL`], wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&s| s == self).unwrap();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Draws the maze in the top-left corner of the canvas, clipped to the
+    /// canvas's size.
+    pub fn draw<C: Canvas + ?Sized>(self, maze: &Maze, canvas: &mut C) {
+        match self {
+            Self::Ascii => maze.draw(canvas),
+            Self::Unicode => maze.draw_glyphs(canvas, ['╱', '╲', '▁']),
+            Self::Braille => draw_braille(maze, canvas),
+        }
+    }
+
+    /// The largest maze dimensions, in cells, that can be drawn on a screen
+    /// of the given size. Leaves the bottom line free for the status bar.
+    pub fn fit_screen(self, width: u16, height: u16) -> (u16, u16) {
+        match self {
+            Self::Ascii | Self::Unicode => Maze::fit_screen(width, height),
+            Self::Braille => {
+                // See `braille_pixel` for the size of the picture in dots.
+                let cols = (2 * width as u32).saturating_sub(1) / 3;
+                let rows = (4 * height.saturating_sub(1) as u32).saturating_sub(3) / 4;
+                if cols < 2 || rows < 1 {
+                    return (0, 0);
+                }
+                (
+                    cols.min(i16::MAX as u32) as u16,
+                    rows.min(i16::MAX as u32 / 2) as u16,
+                )
+            }
+        }
+    }
+
+    /// The cell drawn at a character of the screen, if any.
+    pub fn position_at(self, maze: &Maze, x: u16, y: u16) -> Option<Position> {
+        match self {
+            Self::Ascii | Self::Unicode => maze.position_at(x, y),
+            Self::Braille => {
+                let pos = braille_char_cell(x, y)?;
+                maze.cells.contains(pos).then(|| pos.into())
+            }
+        }
+    }
+
+    /// The two cells either side of a wall drawn at a character of the
+    /// screen. Braille characters are too small to pick out single walls,
+    /// so this is always `None` for [`Style::Braille`].
+    pub fn wall_at(self, maze: &Maze, x: u16, y: u16) -> Option<(Vector, Vector)> {
+        match self {
+            Self::Ascii | Self::Unicode => maze.wall_at(x, y),
+            Self::Braille => None,
+        }
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|style| style.name() == s)
+            .ok_or_else(|| format!("unknown style {s:?}"))
+    }
+}
+
+/// The cell whose inside covers a dot of the braille picture, if any.
+///
+/// Each cell is drawn as a hexagon three dots wide and four tall, sharing
+/// its outline with its neighbors:
+///
+/// ```text
+///  ●●●●
+///  ●  ●
+///  ●  ●●●●
+///  ●  ●  ●
+///  ●●●●  ●
+///     ●  ●
+///     ●●●●
+/// ```
+///
+/// The cell at `(col, half_row)` has its corners at `x` of `3 * col` and
+/// `3 * col + 3`, and `y` of `2 * half_row`, `2 * half_row + 2` and
+/// `2 * half_row + 4`.
+fn braille_pixel(x: i32, y: i32) -> Option<Vector> {
+    if x.rem_euclid(3) == 0 {
+        return None;
+    }
+    let col = x.div_euclid(3);
+    // Of the half rows whose insides span this line, take the one on the
+    // cell grid of this column.
+    let half_row = [(y - 1).div_euclid(2), (y - 3).div_euclid(2)]
+        .into_iter()
+        .find(|half_row| (col + half_row).rem_euclid(2) == 0)?;
+    (1..=3).contains(&(y - 2 * half_row)).then_some(Vector {
+        col: col as i16,
+        half_row: half_row as i16,
+    })
+}
+
+/// Whether the dot at `(x, y)` of the braille picture is part of a wall.
+fn braille_wall(maze: &Maze, x: i32, y: i32) -> bool {
+    if braille_pixel(x, y).is_some() {
+        return false;
+    }
+
+    // Either a corner or a wall between two cells.
+    let (left, right) = if x.rem_euclid(3) == 0 {
+        (braille_pixel(x - 1, y), braille_pixel(x + 1, y))
+    } else {
+        (braille_pixel(x, y - 1), braille_pixel(x, y + 1))
+    };
+    match (left, right) {
+        (Some(a), Some(b)) => {
+            (maze.cells.contains(a) || maze.cells.contains(b)) && maze.is_wall_drawn(a, b)
+        }
+        // A corner, which is drawn if any of the cells around it are.
+        _ => [(-1, -1), (1, -1), (-1, 1), (1, 1), (-1, 0), (1, 0)]
+            .into_iter()
+            .filter_map(|(dx, dy)| braille_pixel(x + dx, y + dy))
+            .any(|pos| maze.cells.contains(pos)),
+    }
+}
+
+/// The cell covering the most dots of a braille character, if any.
+fn braille_char_cell(x: u16, y: u16) -> Option<Vector> {
+    let mut counts: Vec<(Vector, u32)> = Vec::new();
+    for dy in 0..4 {
+        for dx in 0..2 {
+            let Some(pos) = braille_pixel(2 * x as i32 + dx, 4 * y as i32 + dy) else {
+                continue;
+            };
+            match counts.iter_mut().find(|(other, _)| *other == pos) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((pos, 1)),
+            }
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(_, count)| count)
+        .map(|(pos, _)| pos)
+}
+
+fn draw_braille<C: Canvas + ?Sized>(maze: &Maze, canvas: &mut C) {
+    if maze.cells.is_empty() {
+        return;
+    }
+
+    let width = (3 * maze.cells.cols() as u32 + 1).div_ceil(2);
+    let height = (4 * maze.cells.rows() as u32 + 3).div_ceil(4);
+    let (canvas_width, canvas_height) = canvas.size();
+    for y in 0..height.min(canvas_height as u32) as u16 {
+        for x in 0..width.min(canvas_width as u32) as u16 {
+            let mut bits = 0;
+            for (dy, row) in BRAILLE_DOTS.iter().enumerate() {
+                for (dx, bit) in row.iter().enumerate() {
+                    if braille_wall(maze, 2 * x as i32 + dx as i32, 4 * y as i32 + dy as i32) {
+                        bits |= bit;
+                    }
+                }
+            }
+
+            let bg = braille_char_cell(x, y)
+                .and_then(|pos| maze.cells.get(pos))
+                .map_or(CLEAR_COLOR, |cell| cell.background);
+            let ch = char::from_u32(0x2800 + bits).unwrap();
+            canvas.put(
+                x,
+                y,
+                ScreenCell {
+                    ch,
+                    fg: CLEAR_COLOR,
+                    bg,
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        generator::Algorithm,
+        hex::{Position, Vector},
+        maze::Maze,
+        style::{Style, BRAILLE_DOTS},
+        terminal::{Screen, BLUE, CLEAR_COLOR},
+    };
+
+    #[test]
+    fn test_parse() {
+        for style in Style::ALL {
+            assert_eq!(style.name().parse(), Ok(style));
+        }
+        assert!("fancy".parse::<Style>().is_err());
+        assert_eq!(Style::Braille.next(), Style::Ascii);
+    }
+
+    #[test]
+    fn test_unicode() {
+        let mut screen = Screen::new(20, 4);
+        Style::Unicode.draw(&Maze::with_size(3, 1), &mut screen);
+        assert_eq!(
+            screen.text(),
+            " ▁▁▁     ▁▁▁\n╱   ╲▁▁▁╱   ╲\n╲▁▁▁╱   ╲▁▁▁╱\n    ╲▁▁▁╱\n",
+        );
+        assert_eq!(
+            Style::Unicode.position_at(&Maze::with_size(3, 1), 5, 2),
+            Some(Position { col: 1, row: 0 }),
+        );
+    }
+
+    /// The dots of the braille characters on the screen, as `#`s.
+    fn dots(screen: &Screen) -> String {
+        let mut dots = String::new();
+        for line in screen.text().lines() {
+            for dot_row in BRAILLE_DOTS {
+                let mut row = String::new();
+                for ch in line.chars() {
+                    let bits = ch as u32 - 0x2800;
+                    for bit in dot_row {
+                        row.push(if bits & bit != 0 { '#' } else { ' ' });
+                    }
+                }
+                dots += row.trim_end();
+                dots += "\n";
+            }
+        }
+        dots
+    }
+
+    #[test]
+    fn test_braille() {
+        let mut maze = Maze::with_size(2, 1);
+        maze.set_wall_between(Vector::ZERO, Vector::SOUTH_EAST, false);
+        maze.cells[Vector::SOUTH_EAST].background = BLUE;
+
+        let mut screen = Screen::new(4, 2);
+        Style::Braille.draw(&maze, &mut screen);
+        let expected = "\
+####
+#  #
+#  ####
+#     #
+####  #
+   #  #
+   ####
+
+";
+        assert_eq!(dots(&screen), expected);
+        assert_eq!(screen.get(2, 1).unwrap().bg, BLUE);
+        assert_eq!(screen.get(0, 0).unwrap().bg, CLEAR_COLOR);
+    }
+
+    #[test]
+    fn test_braille_position_at() {
+        let maze = Maze::with_size(4, 3);
+        assert_eq!(
+            Style::Braille.position_at(&maze, 0, 0),
+            Some(Position { col: 0, row: 0 })
+        );
+        assert_eq!(
+            Style::Braille.position_at(&maze, 2, 1),
+            Some(Position { col: 1, row: 0 })
+        );
+        assert_eq!(Style::Braille.position_at(&maze, 20, 0), None);
+
+        // Every cell can be picked out somewhere on the screen.
+        for pos in maze.cells.indices() {
+            assert!((0..8)
+                .any(|x| { (0..5).any(|y| Style::Braille.position_at(&maze, x, y) == Some(pos)) }));
+        }
+    }
+
+    #[test]
+    fn test_braille_fits() {
+        let (width, height) = (80, 24);
+        let (cols, rows) = Style::Braille.fit_screen(width, height);
+        let (ascii_cols, ascii_rows) = Style::Ascii.fit_screen(width, height);
+        assert!(cols as u32 * rows as u32 > 4 * ascii_cols as u32 * ascii_rows as u32);
+
+        let mut generator = Algorithm::Dfs.generator(cols, rows, 0);
+        let mut screen = Screen::new(width, height);
+        Style::Braille.draw(generator.finish(), &mut screen);
+        let text = screen.text();
+        let lines: Vec<_> = text.lines().collect();
+        assert!(lines[..height as usize - 1]
+            .iter()
+            .all(|line| !line.is_empty()));
+        assert!(lines[height as usize - 1].is_empty());
+    }
+}
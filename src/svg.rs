@@ -0,0 +1,178 @@
+use std::fmt::Write;
+
+use crate::{
+    color::Color,
+    hex::Vector,
+    maze::Maze,
+    terminal::{BLACK, CLEAR_COLOR, RED, WHITE},
+};
+
+/// Options for rendering a maze with [`render`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SvgOptions {
+    /// The circumradius of each hexagon.
+    pub cell_radius: f64,
+    pub wall_width: f64,
+    pub path_width: f64,
+    /// Blank space around the maze.
+    pub margin: f64,
+    /// Whether to fill cells with their background color.
+    pub fill_backgrounds: bool,
+    pub background_color: Color,
+    pub wall_color: Color,
+    pub path_color: Color,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            cell_radius: 10.0,
+            wall_width: 2.0,
+            path_width: 3.0,
+            margin: 4.0,
+            fill_backgrounds: true,
+            background_color: WHITE,
+            wall_color: BLACK,
+            path_color: RED,
+        }
+    }
+}
+
+/// Renders a maze as an SVG document, optionally with a path through it
+/// such as [`Solver::path`](crate::solver::Solver::path).
+pub fn render(maze: &Maze, path: Option<&[Vector]>, options: &SvgOptions) -> String {
+    let radius = options.cell_radius;
+    let margin = options.margin;
+    let (width, height) = maze.extent(radius);
+    let point = |(x, y): (f64, f64)| (x + margin, y + margin);
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.2}" height="{h:.2}" viewBox="0 0 {w:.2} {h:.2}">"#,
+        w = width + 2.0 * margin,
+        h = height + 2.0 * margin,
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<rect width="100%" height="100%" fill="{}"/>"#,
+        hex_color(options.background_color),
+    )
+    .unwrap();
+
+    if options.fill_backgrounds {
+        for pos in maze.cells.indices() {
+            let background = maze.cells[pos].background;
+            if background == CLEAR_COLOR {
+                continue;
+            }
+
+            let corners = Vector::from(pos).corners(radius).map(point);
+            let mut points = String::new();
+            for (x, y) in corners {
+                write!(points, "{x:.2},{y:.2} ").unwrap();
+            }
+            writeln!(
+                svg,
+                r#"<polygon points="{}" fill="{}"/>"#,
+                points.trim_end(),
+                hex_color(background),
+            )
+            .unwrap();
+        }
+    }
+
+    let mut walls = String::new();
+    for (pos, side) in maze.walls() {
+        let corners = pos.corners(radius).map(point);
+        let (x1, y1) = corners[(side + 4) % 6];
+        let (x2, y2) = corners[(side + 5) % 6];
+        write!(walls, "M{x1:.2} {y1:.2}L{x2:.2} {y2:.2}").unwrap();
+    }
+    if !walls.is_empty() {
+        writeln!(
+            svg,
+            r#"<path d="{walls}" stroke="{}" stroke-width="{:.2}" stroke-linecap="round" fill="none"/>"#,
+            hex_color(options.wall_color),
+            options.wall_width,
+        )
+        .unwrap();
+    }
+
+    if let Some(path) = path.filter(|path| !path.is_empty()) {
+        let mut points = String::new();
+        for &pos in path {
+            let (x, y) = point(pos.center(radius));
+            write!(points, "{x:.2},{y:.2} ").unwrap();
+        }
+        writeln!(
+            svg,
+            r#"<polyline points="{}" stroke="{}" stroke-width="{:.2}" stroke-linecap="round" stroke-linejoin="round" fill="none"/>"#,
+            points.trim_end(),
+            hex_color(options.path_color),
+            options.path_width,
+        )
+        .unwrap();
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn hex_color(color: Color) -> String {
+    match color.rgb() {
+        Some([r, g, b]) => format!("#{r:02x}{g:02x}{b:02x}"),
+        None => "none".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hex::Vector,
+        maze::Maze,
+        svg::{render, SvgOptions},
+        terminal::GREEN,
+    };
+
+    #[test]
+    fn test_render_single_cell() {
+        let svg = render(&Maze::with_size(1, 1), None, &SvgOptions::default());
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches('L').count(), 6);
+        assert!(!svg.contains("<polygon"));
+        assert!(!svg.contains("<polyline"));
+    }
+
+    #[test]
+    fn test_render_only_present_walls() {
+        let mut maze = Maze::with_size(2, 1);
+        let svg = render(&maze, None, &SvgOptions::default());
+        assert_eq!(svg.matches('L').count(), 11);
+
+        maze.set_wall_between(Vector::ZERO, Vector::SOUTH_EAST, false);
+        let svg = render(&maze, None, &SvgOptions::default());
+        assert_eq!(svg.matches('L').count(), 10);
+    }
+
+    #[test]
+    fn test_render_backgrounds_and_path() {
+        let mut maze = Maze::with_size(2, 1);
+        maze.cells[Vector::ZERO].background = GREEN;
+        let path = [Vector::ZERO, Vector::SOUTH_EAST];
+
+        let svg = render(&maze, Some(&path), &SvgOptions::default());
+        assert_eq!(svg.matches("<polygon").count(), 1);
+        assert!(svg.contains(r##"fill="#00cd00""##));
+        assert_eq!(svg.matches("<polyline").count(), 1);
+
+        let options = SvgOptions {
+            fill_backgrounds: false,
+            ..SvgOptions::default()
+        };
+        let svg = render(&maze, Some(&path), &options);
+        assert!(!svg.contains("<polygon"));
+    }
+}
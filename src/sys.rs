@@ -56,6 +56,7 @@ pub mod signal {
     #[repr(i32)]
     pub enum Signal {
         Term = 15,
+        WinCh = 28,
     }
 
     #[repr(C)]
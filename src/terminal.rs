@@ -6,11 +6,15 @@ use std::{
     time::Duration,
 };
 
-use crate::sys::{
-    ioctl::{IOCtl, WinSize},
-    poll::{PollEvents, PollFd},
-    signal::{SigAction, SigActionFlags, SigSet, Signal},
-    termios::{SetAttrActions, Termios},
+use crate::{
+    color::{Color, ColorSupport},
+    input::{decode, Event},
+    sys::{
+        ioctl::{IOCtl, WinSize},
+        poll::{PollEvents, PollFd},
+        signal::{SigAction, SigActionFlags, SigSet, Signal},
+        termios::{SetAttrActions, Termios},
+    },
 };
 
 pub const fn ctrl(c: u8) -> u8 {
@@ -21,29 +25,61 @@ pub const CTRL_C: u8 = ctrl(b'c');
 pub const ESC: u8 = ctrl(b'[');
 
 static EXIT: AtomicBool = AtomicBool::new(false);
-
-pub const BLACK: u8 = 0;
-pub const RED: u8 = 1;
-pub const GREEN: u8 = 2;
-pub const YELLOW: u8 = 3;
-pub const BLUE: u8 = 4;
-pub const MAGENTA: u8 = 5;
-pub const CYAN: u8 = 6;
-pub const LIGHT_GREY: u8 = 7;
-pub const GRAY: u8 = 8;
-pub const LIGHT_RED: u8 = 9;
-pub const LIGHT_GREEN: u8 = 10;
-pub const LIGHT_YELLOW: u8 = 11;
-pub const LIGHT_BLUE: u8 = 12;
-pub const LIGHT_MAGENTA: u8 = 13;
-pub const LIGHT_CYAN: u8 = 14;
-pub const WHITE: u8 = 15;
-pub const CLEAR_COLOR: u8 = 16;
+static RESIZED: AtomicBool = AtomicBool::new(false);
+
+pub const BLACK: Color = Color::Basic(0);
+pub const RED: Color = Color::Basic(1);
+pub const GREEN: Color = Color::Basic(2);
+pub const YELLOW: Color = Color::Basic(3);
+pub const BLUE: Color = Color::Basic(4);
+pub const MAGENTA: Color = Color::Basic(5);
+pub const CYAN: Color = Color::Basic(6);
+pub const LIGHT_GREY: Color = Color::Basic(7);
+pub const GRAY: Color = Color::Basic(8);
+pub const LIGHT_RED: Color = Color::Basic(9);
+pub const LIGHT_GREEN: Color = Color::Basic(10);
+pub const LIGHT_YELLOW: Color = Color::Basic(11);
+pub const LIGHT_BLUE: Color = Color::Basic(12);
+pub const LIGHT_MAGENTA: Color = Color::Basic(13);
+pub const LIGHT_CYAN: Color = Color::Basic(14);
+pub const WHITE: Color = Color::Basic(15);
+pub const CLEAR_COLOR: Color = Color::Default;
+
+/// RGB values for the 16 basic colors, for rendering outside the terminal.
+pub const PALETTE: [[u8; 3]; 16] = [
+    [0x00, 0x00, 0x00],
+    [0xcd, 0x00, 0x00],
+    [0x00, 0xcd, 0x00],
+    [0xcd, 0xcd, 0x00],
+    [0x00, 0x00, 0xee],
+    [0xcd, 0x00, 0xcd],
+    [0x00, 0xcd, 0xcd],
+    [0xe5, 0xe5, 0xe5],
+    [0x7f, 0x7f, 0x7f],
+    [0xff, 0x00, 0x00],
+    [0x00, 0xff, 0x00],
+    [0xff, 0xff, 0x00],
+    [0x5c, 0x5c, 0xff],
+    [0xff, 0x00, 0xff],
+    [0x00, 0xff, 0xff],
+    [0xff, 0xff, 0xff],
+];
+
+/// How long to wait after an ESC byte for the rest of an escape sequence,
+/// before deciding that the Escape key was pressed on its own.
+pub const ESC_TIMEOUT: Duration = Duration::from_millis(25);
 
 pub struct Terminal {
     stdin: io::StdinLock<'static>,
     stdout: io::StdoutLock<'static>,
     old_termios: Termios,
+    /// Bytes that have been read but not yet consumed.
+    input: Vec<u8>,
+    /// Colors drawn directly are downgraded to what the terminal supports.
+    pub color_support: ColorSupport,
+    /// The size as of the last [`Event::Resize`], so that drawing doesn't
+    /// have to ask the terminal for it.
+    size: (u16, u16),
 }
 
 pub struct SGR<'a> {
@@ -56,7 +92,11 @@ impl Terminal {
             stdin: io::stdin().lock(),
             stdout: io::stdout().lock(),
             old_termios: Termios::zeros(),
+            input: Vec::new(),
+            color_support: ColorSupport::detect(),
+            size: (0, 0),
         };
+        term.size = term.query_size();
 
         extern "C" fn sigkill_handler(_: Signal) {
             EXIT.store(true, Ordering::SeqCst);
@@ -71,6 +111,19 @@ impl Terminal {
             None,
         );
 
+        extern "C" fn sigwinch_handler(_: Signal) {
+            RESIZED.store(true, Ordering::SeqCst);
+        }
+        sigaction(
+            Signal::WinCh,
+            Some(&SigAction {
+                handler: sigwinch_handler,
+                mask: SigSet::default(),
+                flags: SigActionFlags::none(),
+            }),
+            None,
+        );
+
         let fd = term.fd();
         let mut termios = tcgetattr(fd);
         term.old_termios = termios;
@@ -83,9 +136,18 @@ impl Terminal {
     }
 }
 
+impl Default for Terminal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.alt_screen(false).cursor_visible(true).flush();
+        self.mouse_reporting(false)
+            .alt_screen(false)
+            .cursor_visible(true)
+            .flush();
 
         tcsetattr(self.fd(), SetAttrActions::Drain, &self.old_termios);
     }
@@ -96,19 +158,87 @@ impl Terminal {
         EXIT.load(Ordering::SeqCst)
     }
 
+    /// Waits up to `timeout` for input, returning the number of bytes ready.
     pub fn poll(&mut self, timeout: Duration) -> u32 {
+        if !self.input.is_empty() {
+            return self.input.len() as u32;
+        }
+
         let mut poll_fds = [PollFd {
             fd: self.fd(),
             events: PollEvents::In,
             revents: PollEvents::none(),
         }];
-        poll(&mut poll_fds, timeout)
+        if poll(&mut poll_fds, timeout) > 0 {
+            self.fill_input();
+        }
+        self.input.len() as u32
     }
 
+    /// Reads whatever input is available without blocking for more.
+    fn fill_input(&mut self) {
+        // At least as large as stdin's own buffer, so that reads bypass it and
+        // nothing is left there where `poll` cannot see it.
+        let mut buf = [0; 8192];
+        match self.stdin.read(&mut buf) {
+            Ok(len) => self.input.extend_from_slice(&buf[..len]),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Reads a single raw byte, blocking until one is available.
     pub fn read(&mut self) -> u8 {
-        let mut buf = [0];
-        self.stdin.read(&mut buf).unwrap();
-        buf[0]
+        while self.input.is_empty() {
+            self.fill_input();
+        }
+        self.input.remove(0)
+    }
+
+    /// Waits up to `timeout` for a key press or mouse event and decodes it.
+    /// A resize interrupts the wait and is reported as [`Event::Resize`].
+    pub fn read_event(&mut self, timeout: Duration) -> Option<Event> {
+        if self.poll(timeout) == 0 {
+            return self.take_resize();
+        }
+
+        loop {
+            if let Some((event, len)) = decode(&self.input, false) {
+                self.input.drain(..len);
+                return Some(event);
+            }
+
+            let len = self.input.len();
+            if self.poll_more(ESC_TIMEOUT) == len {
+                let (event, len) = decode(&self.input, true)?;
+                self.input.drain(..len);
+                return Some(event);
+            }
+        }
+    }
+
+    fn take_resize(&mut self) -> Option<Event> {
+        if RESIZED.swap(false, Ordering::SeqCst) {
+            self.size = self.query_size();
+            let (width, height) = self.size;
+            Some(Event::Resize(width, height))
+        } else {
+            None
+        }
+    }
+
+    /// Waits up to `timeout` for more input than is already buffered,
+    /// returning the new amount buffered.
+    fn poll_more(&mut self, timeout: Duration) -> usize {
+        let mut poll_fds = [PollFd {
+            fd: self.fd(),
+            events: PollEvents::In,
+            revents: PollEvents::none(),
+        }];
+        if poll(&mut poll_fds, timeout) > 0 {
+            self.fill_input();
+        }
+        self.input.len()
     }
 
     pub fn write(&mut self, s: impl AsRef<[u8]>) -> &mut Self {
@@ -116,6 +246,12 @@ impl Terminal {
         self
     }
 
+    /// Updates the terminal to show the frame drawn on `screen`.
+    pub fn present(&mut self, screen: &mut Screen) -> &mut Self {
+        let bytes = screen.render();
+        self.write(bytes)
+    }
+
     pub fn flush(&mut self) {
         self.stdout.flush().unwrap();
     }
@@ -128,6 +264,11 @@ impl Terminal {
         self.csi().write("2J")
     }
 
+    /// Clears from the cursor to the end of the line.
+    pub fn clear_line(&mut self) -> &mut Self {
+        self.csi().write("K")
+    }
+
     pub fn goto(&mut self, x: u16, y: u16) -> &mut Self {
         let row = y + 1;
         let col = x + 1;
@@ -144,6 +285,15 @@ impl Terminal {
         }
     }
 
+    /// Reports mouse presses, releases, drags and scrolling as SGR sequences.
+    pub fn mouse_reporting(&mut self, enable: bool) -> &mut Self {
+        if enable {
+            self.csi().write("?1002h").csi().write("?1006h")
+        } else {
+            self.csi().write("?1006l").csi().write("?1002l")
+        }
+    }
+
     /// Alternate screen buffer
     fn alt_screen(&mut self, enable: bool) -> &mut Self {
         if enable {
@@ -154,35 +304,209 @@ impl Terminal {
     }
 
     /// https://en.wikipedia.org/wiki/ANSI_escape_code#Select_Graphic_Rendition_parameters
-    pub fn sgr(&mut self) -> SGR {
+    pub fn sgr(&mut self) -> SGR<'_> {
         SGR { term: self }
     }
 }
 
 impl SGR<'_> {
-    fn write(&mut self, n: u8) -> &mut Self {
+    fn write(&mut self, params: &str) -> &mut Self {
         self.term.csi();
-        write!(self.term.stdout, "{n}m").unwrap();
+        write!(self.term.stdout, "{params}m").unwrap();
         self
     }
 
     pub fn reset(&mut self) -> &mut Self {
-        self.write(0)
+        self.write("0")
+    }
+
+    pub fn fg(&mut self, color: Color) -> &mut Self {
+        self.write(&color.sgr_params(false))
     }
 
-    pub fn fg(&mut self, color: u8) -> &mut Self {
-        match color % 16 {
-            0..=7 => self.write(30 + color),
-            8..=15 => self.write(90 + color - 8),
-            _ => self.write(38),
+    pub fn bg(&mut self, color: Color) -> &mut Self {
+        self.write(&color.sgr_params(true))
+    }
+}
+
+/// A character on a [`Screen`], with its colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenCell {
+    pub ch: char,
+    pub fg: Color,
+    pub bg: Color,
+}
+
+impl Default for ScreenCell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            fg: CLEAR_COLOR,
+            bg: CLEAR_COLOR,
         }
     }
+}
+
+/// Something that styled characters can be drawn on.
+pub trait Canvas {
+    fn size(&self) -> (u16, u16);
+
+    /// Sets the character at `(x, y)`, ignoring positions off the canvas.
+    fn put(&mut self, x: u16, y: u16, cell: ScreenCell);
 
-    pub fn bg(&mut self, color: u8) -> &mut Self {
-        match color % 16 {
-            0..=7 => self.write(40 + color),
-            8..=15 => self.write(100 + color - 8),
-            _ => self.write(48),
+    /// Writes `s` from `(x, y)` rightwards, clipped to the canvas.
+    fn write_str(&mut self, x: u16, y: u16, s: &str, fg: Color, bg: Color) {
+        let (width, _) = self.size();
+        for (ch, x) in s.chars().zip(x..width) {
+            self.put(x, y, ScreenCell { ch, fg, bg });
+        }
+    }
+}
+
+/// A virtual screen that is drawn into, then rendered to the terminal by
+/// emitting only the characters that changed since the last frame.
+pub struct Screen {
+    width: u16,
+    height: u16,
+    /// What the terminal is currently showing.
+    front: Vec<ScreenCell>,
+    /// The frame being drawn.
+    back: Vec<ScreenCell>,
+    /// Whether the terminal's contents are unknown, so the next frame must
+    /// clear it first.
+    invalid: bool,
+    /// Colors are downgraded to what the terminal supports when rendered.
+    pub color_support: ColorSupport,
+}
+
+impl Screen {
+    pub fn new(width: u16, height: u16) -> Self {
+        let len = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            front: vec![ScreenCell::default(); len],
+            back: vec![ScreenCell::default(); len],
+            invalid: true,
+            color_support: ColorSupport::TrueColor,
+        }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// Resizes the screen, blanking it if the size changed. Either way, the
+    /// next frame is drawn in full, since the terminal may have reflowed or
+    /// cleared what was on it.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        if (width, height) != self.size() {
+            *self = Self {
+                color_support: self.color_support,
+                ..Self::new(width, height)
+            };
+        }
+        self.invalidate();
+    }
+
+    /// Forces the next frame to be drawn in full, e.g. after something else
+    /// has written to the terminal.
+    pub fn invalidate(&mut self) {
+        self.invalid = true;
+    }
+
+    /// Blanks the frame being drawn.
+    pub fn clear(&mut self) {
+        self.back.fill(ScreenCell::default());
+    }
+
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// The character at `(x, y)` in the frame being drawn.
+    pub fn get(&self, x: u16, y: u16) -> Option<ScreenCell> {
+        self.index(x, y).map(|i| self.back[i])
+    }
+
+    /// The characters of the frame being drawn, without colors, with
+    /// trailing whitespace trimmed from each line.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for row in self.back.chunks(self.width.max(1) as usize) {
+            let line: String = row.iter().map(|cell| cell.ch).collect();
+            text += line.trim_end();
+            text += "\n";
+        }
+        text
+    }
+
+    /// Returns the bytes that update the terminal from the last frame to
+    /// the one that has been drawn, which becomes the last frame.
+    pub fn render(&mut self) -> Vec<u8> {
+        let mut out = Vec::new();
+        if self.invalid {
+            out.extend_from_slice(b"\x1b[0m\x1b[2J");
+            self.front.fill(ScreenCell::default());
+            self.invalid = false;
+        }
+
+        // The cursor position and colors, where they are known.
+        let mut cursor = None;
+        let mut colors = (CLEAR_COLOR, CLEAR_COLOR);
+        let width = self.width as usize;
+        for (i, (&cell, front)) in self.back.iter().zip(&mut self.front).enumerate() {
+            if cell == *front {
+                continue;
+            }
+            *front = cell;
+
+            let (x, y) = (i % width, i / width);
+            match cursor {
+                Some((cx, cy)) if (cx, cy) == (x, y) => {}
+                Some((cx, cy)) if cy == y && cx < x => {
+                    write!(out, "\x1b[{}C", x - cx).unwrap();
+                }
+                _ => write!(out, "\x1b[{};{}H", y + 1, x + 1).unwrap(),
+            }
+            let fg = cell.fg.downgrade(self.color_support);
+            let bg = cell.bg.downgrade(self.color_support);
+            if colors != (fg, bg) {
+                colors = (fg, bg);
+                out.extend_from_slice(b"\x1b[0");
+                if fg != Color::Default {
+                    write!(out, ";{}", fg.sgr_params(false)).unwrap();
+                }
+                if bg != Color::Default {
+                    write!(out, ";{}", bg.sgr_params(true)).unwrap();
+                }
+                out.push(b'm');
+            }
+            write!(out, "{}", cell.ch).unwrap();
+
+            // Writing the last column leaves the cursor's position up to the
+            // terminal, so move it explicitly next time.
+            cursor = (x + 1 < width).then_some((x + 1, y));
+        }
+        if colors != (CLEAR_COLOR, CLEAR_COLOR) {
+            out.extend_from_slice(b"\x1b[0m");
+        }
+        out
+    }
+}
+
+impl Canvas for Screen {
+    fn size(&self) -> (u16, u16) {
+        Screen::size(self)
+    }
+
+    fn put(&mut self, x: u16, y: u16, cell: ScreenCell) {
+        if let Some(i) = self.index(x, y) {
+            self.back[i] = cell;
         }
     }
 }
@@ -193,21 +517,61 @@ impl Terminal {
     }
 
     pub fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    fn query_size(&self) -> (u16, u16) {
         let size = ioctl_winsize(self.fd());
         (size.col, size.row)
     }
 }
 
+/// Draws straight to the terminal, rewriting every character drawn. See
+/// [`Screen`] for drawing only what changes.
+impl Canvas for Terminal {
+    fn size(&self) -> (u16, u16) {
+        Terminal::size(self)
+    }
+
+    fn put(&mut self, x: u16, y: u16, cell: ScreenCell) {
+        let mut buf = [0; 4];
+        self.write_str(x, y, cell.ch.encode_utf8(&mut buf), cell.fg, cell.bg);
+    }
+
+    fn write_str(&mut self, x: u16, y: u16, s: &str, fg: Color, bg: Color) {
+        let (width, height) = Terminal::size(self);
+        if x >= width || y >= height {
+            return;
+        }
+        let len = s
+            .char_indices()
+            .nth((width - x) as usize)
+            .map_or(s.len(), |(i, _)| i);
+
+        let support = self.color_support;
+        self.goto(x, y)
+            .sgr()
+            .fg(fg.downgrade(support))
+            .bg(bg.downgrade(support));
+        self.write(&s[..len]);
+        self.sgr().reset();
+    }
+}
+
 fn poll(poll_fds: &mut [PollFd], timeout: Duration) -> u32 {
     let res;
     unsafe {
         res = crate::sys::poll::poll(
             poll_fds.as_mut_ptr(),
             poll_fds.len(),
-            timeout.as_millis() as i32,
+            timeout.as_millis().min(i32::MAX as u128) as i32,
         );
     }
     if res < 0 {
+        // A signal such as SIGWINCH arrived before any input did.
+        if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+            return 0;
+        }
         panic!("poll failed");
     } else {
         res as u32
@@ -251,3 +615,99 @@ fn ioctl_winsize(fd: RawFd) -> WinSize {
     }
     size
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        color::{Color, ColorSupport},
+        generator::Algorithm,
+        hex::Vector,
+        terminal::{Canvas, Screen, ScreenCell, CLEAR_COLOR, GREEN, LIGHT_RED},
+    };
+
+    fn render_str(screen: &mut Screen) -> String {
+        String::from_utf8(screen.render()).unwrap()
+    }
+
+    #[test]
+    fn test_render_changes_only() {
+        let mut screen = Screen::new(10, 3);
+        screen.write_str(2, 1, "abc", CLEAR_COLOR, CLEAR_COLOR);
+        assert_eq!(render_str(&mut screen), "\x1b[0m\x1b[2J\x1b[2;3Habc");
+
+        // Redrawing the same frame writes nothing.
+        screen.clear();
+        screen.write_str(2, 1, "abc", CLEAR_COLOR, CLEAR_COLOR);
+        assert_eq!(render_str(&mut screen), "");
+
+        screen.put(
+            4,
+            1,
+            ScreenCell {
+                ch: 'd',
+                fg: CLEAR_COLOR,
+                bg: GREEN,
+            },
+        );
+        screen.write_str(7, 1, "e", LIGHT_RED, CLEAR_COLOR);
+        screen.write_str(0, 2, "f", CLEAR_COLOR, CLEAR_COLOR);
+        assert_eq!(
+            render_str(&mut screen),
+            "\x1b[2;5H\x1b[0;42md\x1b[2C\x1b[0;91me\x1b[3;1H\x1b[0mf",
+        );
+    }
+
+    #[test]
+    fn test_resize_redraws() {
+        let mut screen = Screen::new(10, 3);
+        screen.write_str(2, 1, "abc", CLEAR_COLOR, CLEAR_COLOR);
+        render_str(&mut screen);
+
+        // Even at the same size, the whole frame is drawn again.
+        screen.resize(10, 3);
+        screen.write_str(2, 1, "abc", CLEAR_COLOR, CLEAR_COLOR);
+        assert_eq!(render_str(&mut screen), "\x1b[0m\x1b[2J\x1b[2;3Habc");
+
+        screen.resize(4, 2);
+        assert_eq!(screen.size(), (4, 2));
+        assert_eq!(render_str(&mut screen), "\x1b[0m\x1b[2J");
+    }
+
+    #[test]
+    fn test_render_downgrades() {
+        let mut screen = Screen::new(2, 1);
+        screen.color_support = ColorSupport::Indexed;
+        screen.write_str(0, 0, "ab", Color::Rgb(0, 0, 0xff), Color::Indexed(250));
+        assert_eq!(
+            render_str(&mut screen),
+            "\x1b[0m\x1b[2J\x1b[1;1H\x1b[0;38;5;21;48;5;250mab\x1b[0m",
+        );
+    }
+
+    #[test]
+    fn test_render_clips() {
+        let mut screen = Screen::new(3, 1);
+        screen.write_str(1, 0, "abcd", CLEAR_COLOR, CLEAR_COLOR);
+        screen.write_str(0, 1, "e", CLEAR_COLOR, CLEAR_COLOR);
+        assert_eq!(screen.get(2, 0).map(|cell| cell.ch), Some('b'));
+        assert_eq!(render_str(&mut screen), "\x1b[0m\x1b[2J\x1b[1;2Hab");
+    }
+
+    #[test]
+    fn test_render_maze_frame() {
+        let mut maze = Algorithm::Dfs.generator(20, 10, 0).finish().clone();
+
+        let mut screen = Screen::new(81, 23);
+        maze.draw(&mut screen);
+        let full = screen.render().len();
+
+        maze.cells[Vector::ZERO].background = GREEN;
+        screen.clear();
+        maze.draw(&mut screen);
+        let frame = screen.render().len();
+
+        // One cell's background is a few characters, not the whole maze.
+        assert!(frame > 0);
+        assert!(frame * 50 < full, "{frame} bytes of {full}");
+    }
+}
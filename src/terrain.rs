@@ -0,0 +1,130 @@
+use rand::Rng;
+
+use crate::{
+    color::Color,
+    hex::{Position, Vector},
+    maze::Maze,
+    terminal::CLEAR_COLOR,
+};
+
+/// What the ground of a cell is like, which makes it quicker or slower to
+/// cross. Only [`Solver`](crate::solver::Solver) takes it into account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Terrain {
+    Road,
+    #[default]
+    Plain,
+    Mud,
+    Water,
+}
+
+impl Terrain {
+    pub const ALL: [Self; 4] = [Self::Road, Self::Plain, Self::Mud, Self::Water];
+
+    /// The cost of stepping into a cell. Plain ground costs the same
+    /// everywhere, so a maze without terrain is solved by counting steps.
+    pub fn cost(self) -> i32 {
+        match self {
+            Self::Road => 1,
+            Self::Plain => 2,
+            Self::Mud => 4,
+            Self::Water => 8,
+        }
+    }
+
+    /// The background of a cell that nothing else is being shown in.
+    pub fn background(self) -> Color {
+        match self {
+            Self::Road => Color::Rgb(0x9e, 0x9e, 0x9e),
+            Self::Plain => CLEAR_COLOR,
+            Self::Mud => Color::Rgb(0xaf, 0x87, 0x00),
+            Self::Water => Color::Rgb(0x5f, 0xaf, 0xd7),
+        }
+    }
+}
+
+/// Covers the maze with patches of mud and water, then lays roads from its
+/// left edge to its right, crossing anything in their way.
+pub fn scatter(maze: &mut Maze, rng: &mut impl Rng) {
+    let (cols, rows) = (maze.cells.cols() as i16, maze.cells.rows() as i16);
+    if cols == 0 || rows == 0 {
+        return;
+    }
+
+    // About one patch for every 30 cells.
+    for _ in 0..(cols as usize * rows as usize / 30).max(1) {
+        let terrain = if rng.gen_bool(0.5) {
+            Terrain::Mud
+        } else {
+            Terrain::Water
+        };
+        let center = Vector::from(Position {
+            col: rng.gen_range(0..cols),
+            row: rng.gen_range(0..rows),
+        });
+        let radius = rng.gen_range(1..=2);
+        for pos in maze.cells.indices() {
+            if (Vector::from(pos) - center).distance() <= radius {
+                maze.terrain[pos] = terrain;
+            }
+        }
+    }
+
+    for _ in 0..(rows / 8).max(1) {
+        let mut pos = Vector::from(Position {
+            col: 0,
+            row: rng.gen_range(0..rows),
+        });
+        while maze.cells.contains(pos) {
+            maze.terrain[pos] = Terrain::Road;
+            // Mostly heading east, never leaving by the top or bottom.
+            let dirs = [
+                Vector::NORTH,
+                Vector::SOUTH,
+                Vector::NORTH_EAST,
+                Vector::NORTH_EAST,
+                Vector::SOUTH_EAST,
+                Vector::SOUTH_EAST,
+            ];
+            let dirs: Vec<_> = dirs
+                .into_iter()
+                .filter(|&dir| maze.cells.contains(pos + dir) || (pos + dir).col >= cols)
+                .collect();
+            pos = pos + dirs[rng.gen_range(0..dirs.len())];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::{
+        generator::SeededRng,
+        maze::Maze,
+        terrain::{scatter, Terrain},
+    };
+
+    #[test]
+    fn test_scatter() {
+        let mut maze = Maze::with_size(20, 10);
+        scatter(&mut maze, &mut SeededRng::seed_from_u64(1));
+        for terrain in Terrain::ALL {
+            assert!(
+                maze.terrain
+                    .indices()
+                    .any(|pos| maze.terrain[pos] == terrain),
+                "{terrain:?}"
+            );
+        }
+        // Each road crosses from the left edge to the right.
+        assert!(maze
+            .terrain
+            .indices()
+            .any(|pos| pos.col == 19 && maze.terrain[pos] == Terrain::Road));
+
+        let mut again = Maze::with_size(20, 10);
+        scatter(&mut again, &mut SeededRng::seed_from_u64(1));
+        assert_eq!(again, maze);
+    }
+}